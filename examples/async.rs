@@ -0,0 +1,51 @@
+extern crate julia;
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake};
+use std::thread::{self, Thread};
+
+use julia::api::{JuliaExecutor, JlValue, Task};
+
+// A minimal executor, just enough to drive the futures below. In practice this
+// would be tokio or async-std.
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+fn block_on<F: Future>(mut future: F) -> F::Output {
+    let waker = Arc::new(ThreadWaker(thread::current())).into();
+    let mut cx = Context::from_waker(&waker);
+    let mut future = unsafe { Pin::new_unchecked(&mut future) };
+
+    loop {
+        match future.as_mut().poll(&mut cx) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => thread::park(),
+        }
+    }
+}
+
+fn main() {
+    let jl = JuliaExecutor::new().unwrap();
+
+    // a task that sleeps doesn't block the requests queued after it.
+    let slow = jl.task_async::<_, f64>(|jl| {
+        let task = jl.eval_string("@schedule (sleep(0.5); 42.0)")?;
+        Task::from_value(task)
+    });
+
+    let fast = jl.eval_async::<_, f64>("sqrt(2.0)");
+    let call = jl.call_async::<_, _, f64>("Base.max", vec![1.0, 3.0, 2.0]);
+
+    println!("sqrt(2.0) = {:?}", block_on(fast));
+    println!("max(1.0, 3.0, 2.0) = {:?}", block_on(call));
+    println!("slow task = {:?}", block_on(slow));
+
+    jl.shutdown().unwrap();
+}
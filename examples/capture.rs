@@ -0,0 +1,23 @@
+extern crate julia;
+
+use std::fs::File;
+
+use julia::api::Julia;
+
+fn main() {
+    let mut jl = Julia::new().unwrap();
+
+    let (ret, output) = jl.capture(|jl| jl.eval_string("println(\"Hello from Julia!\"); 42"));
+    println!("returned {:?}, printed {:?}", ret.map(|x| x.to_string()), output);
+
+    // exceptions don't lose the output printed before them.
+    let (ret, output) = jl.capture(|jl| jl.eval_string("print(\"partial\"); error(\"oops\")"));
+    println!("failed: {}, printed {:?}", ret.is_err(), output);
+
+    let log = File::create("julia.log").unwrap();
+    jl.redirect_stdout(log).unwrap();
+    jl.eval_string("for i in 1:3; println(\"line \", i); end").unwrap();
+    jl.restore_stdout().unwrap();
+
+    jl.eval_string("println(\"back on the terminal\")").unwrap();
+}
@@ -0,0 +1,62 @@
+extern crate julia;
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::sync::mpsc;
+use std::task::{Context, Poll, Wake};
+use std::thread::{self, Thread};
+
+use julia::api::{JuliaExecutor, JlChannel};
+
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+fn block_on<F: Future>(mut future: F) -> F::Output {
+    let waker = Arc::new(ThreadWaker(thread::current())).into();
+    let mut cx = Context::from_waker(&waker);
+    let mut future = unsafe { Pin::new_unchecked(&mut future) };
+
+    loop {
+        match future.as_mut().poll(&mut cx) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => thread::park(),
+        }
+    }
+}
+
+fn main() {
+    let exec = JuliaExecutor::new().unwrap();
+
+    // Rust -> Julia: feed a Channel from an mpsc Receiver.
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || for i in 1..6 {
+        tx.send(i as f64).unwrap();
+    });
+
+    let sum = exec.run(move |_| {
+        let channel = JlChannel::<f64>::new(16)?;
+        channel.feed(rx)?;
+        channel.iter().sum::<julia::error::Result<f64>>()
+    });
+    println!("sum = {:?}", sum);
+
+    // Julia -> Rust: a producer Task on the executor, consumed as a stream.
+    let mut squares = exec.stream::<_, f64>(|jl| {
+        let channel = jl.eval_string(
+            "Channel(c -> for i in 1:5; put!(c, i^2 / 1.0); sleep(0.1); end)",
+        )?;
+        JlChannel::with_value(channel)
+    });
+
+    while let Some(x) = block_on(squares.next()) {
+        println!("square = {:?}", x);
+    }
+
+    exec.shutdown().unwrap();
+}
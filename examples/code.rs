@@ -0,0 +1,29 @@
+extern crate julia;
+
+use julia::api::{Julia, Value, JlValue, Expr, Datatype};
+
+fn main() {
+    let mut jl = Julia::new().unwrap();
+
+    let ex = Expr::with_string("@assert x > 0").unwrap();
+    let expanded = ex.macroexpand(jl.main()).unwrap();
+    println!("expanded: {}", expanded);
+
+    let ex = Expr::with_string("for i in 1:3 println(i) end").unwrap();
+    if let Some(lowered) = ex.lower(jl.main()).unwrap() {
+        for stmt in lowered.code().unwrap() {
+            println!("    {}", stmt);
+        }
+    }
+
+    jl.eval_string("square(x) = x * x").unwrap();
+    let square = jl.main().function("square").unwrap();
+    let float64 = Value::from_value(Datatype::float64()).unwrap();
+    let types = [float64];
+
+    for (code, rettype) in square.code_typed(&types).unwrap() {
+        println!("square(::Float64)::{} has slots {:?}", rettype, code.slot_names().unwrap());
+    }
+    println!("{}", square.code_llvm(&types).unwrap());
+    println!("{}", square.code_native(&types).unwrap());
+}
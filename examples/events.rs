@@ -0,0 +1,24 @@
+extern crate julia;
+
+use std::thread;
+use std::time::Duration;
+
+use julia::api::Julia;
+
+fn main() {
+    let mut jl = Julia::new().unwrap();
+
+    // Neither the Timer nor the @async Task make progress unless events are
+    // processed.
+    jl.eval_string("t = Timer(_ -> println(\"tick\"), 0.1, 0.1)")
+        .unwrap();
+    jl.eval_string("@async (sleep(0.35); println(\"woke up\"); close(t))")
+        .unwrap();
+
+    // a host event loop would wait on jl.event_fd() instead of sleeping.
+    for _ in 0..50 {
+        jl.run_until_idle().unwrap();
+        let timeout = jl.event_timeout().unwrap_or(Duration::from_millis(10));
+        thread::sleep(timeout.min(Duration::from_millis(10)));
+    }
+}
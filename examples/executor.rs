@@ -0,0 +1,31 @@
+extern crate julia;
+
+use std::thread;
+
+use julia::api::{JuliaExecutor, JlValue};
+
+fn main() {
+    let jl = JuliaExecutor::new().unwrap();
+
+    let workers: Vec<_> = (1..5)
+        .map(|i| {
+            let jl = jl.clone();
+            thread::spawn(move || {
+                let x: f64 = jl.eval(format!("sqrt({}.0)", i)).unwrap();
+                println!("sqrt({}) = {}", i, x);
+            })
+        })
+        .collect();
+
+    for worker in workers {
+        worker.join().unwrap();
+    }
+
+    let typename = jl.run(|jl| {
+        let x = jl.eval_string("1 + 2im")?;
+        x.typename()
+    });
+    println!("typeof(1 + 2im) = {:?}", typename);
+
+    jl.shutdown().unwrap();
+}
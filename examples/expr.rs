@@ -0,0 +1,59 @@
+extern crate julia;
+
+use std::convert::TryFrom;
+
+use julia::api::{Julia, Value, JlValue, Symbol, Expr, ExprVisitor};
+use julia::api::expr::{quote_node, global_ref};
+use julia::error::Result;
+
+/// Collects the names used in an expression.
+struct Names(Vec<String>);
+
+impl ExprVisitor for Names {
+    fn visit_symbol(&mut self, sym: &Symbol) -> Result<()> {
+        self.0.push(String::try_from(sym)?);
+        Ok(())
+    }
+}
+
+fn sym(name: &str) -> Value {
+    Value::from_value(Symbol::with_name(name).unwrap()).unwrap()
+}
+
+fn main() {
+    let jl = Julia::new().unwrap();
+
+    // square(x) = x * x
+    let signature = Expr::call("square", &[sym("x")]).unwrap();
+    let body = Expr::call("*", &[sym("x"), sym("x")]).unwrap();
+    let def = Expr::new(
+        "=",
+        &[
+            Value::from_value(signature).unwrap(),
+            Value::from_value(body).unwrap(),
+        ],
+    ).unwrap();
+    println!("{}", def.to_value::<_, Value>().unwrap());
+    jl.main().eval(&def).unwrap();
+
+    // Base.sqrt(square(4)), no matter what sqrt means in Main.
+    let square = Expr::call("square", &[Value::from(4i64)]).unwrap();
+    let call = Expr::new(
+        "call",
+        &[
+            global_ref(jl.base(), "sqrt").unwrap(),
+            Value::from_value(square).unwrap(),
+        ],
+    ).unwrap();
+    println!("{}", jl.main().eval(&call).unwrap());
+
+    // a quoted symbol evaluates to the symbol itself.
+    let quoted = Expr::new("block", &[quote_node(&sym("hello")).unwrap()]).unwrap();
+    println!("{}", jl.main().eval(&quoted).unwrap());
+
+    println!("head: {}, {} args", def.head_name().unwrap(), def.nargs().unwrap());
+
+    let mut names = Names(vec![]);
+    def.walk(&mut names).unwrap();
+    println!("names: {:?}", names.0);
+}
@@ -0,0 +1,28 @@
+extern crate julia;
+
+use julia::api::Julia;
+
+fn main() {
+    let mut jl = Julia::new().unwrap();
+
+    let before = jl.gc().stats();
+    jl.eval_string("[rand(1000) for _ in 1:10_000]").unwrap();
+    jl.gc_mut().collect(true).unwrap();
+    let stats = jl.gc().stats().since(&before);
+
+    println!("allocations: {}", stats.allocations());
+    println!(
+        "collections: {} ({} full, {} incremental)",
+        stats.collections,
+        stats.full_collections,
+        stats.incremental_collections()
+    );
+    println!("time spent collecting: {:?}", stats.total_time);
+
+    {
+        let _guard = jl.gc_mut().disable();
+        assert!(!jl.gc().is_enabled());
+        jl.eval_string("[rand(1000) for _ in 1:1_000]").unwrap();
+    }
+    assert!(jl.gc().is_enabled());
+}
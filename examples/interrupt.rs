@@ -0,0 +1,30 @@
+extern crate julia;
+
+use std::thread;
+use std::time::Duration;
+
+use julia::api::Julia;
+use julia::error::Error;
+
+fn main() {
+    let mut jl = Julia::new().unwrap();
+
+    let handle = jl.interrupt_handle();
+    thread::spawn(move || {
+        thread::sleep(Duration::from_millis(500));
+        handle.interrupt().unwrap();
+    });
+
+    match jl.eval_string("while true; sleep(0.01); end") {
+        Err(Error::UnhandledException(ex)) => println!("interrupted: {}", ex),
+        ret => println!("unexpected: {:?}", ret),
+    }
+
+    match jl.eval_with_timeout("while true; sleep(0.01); end", Duration::from_millis(500)) {
+        Err(Error::Timeout) => println!("timed out"),
+        ret => println!("unexpected: {:?}", ret),
+    }
+
+    let x = jl.eval_with_timeout("1 + 2", Duration::from_secs(1)).unwrap();
+    println!("1 + 2 = {}", x);
+}
@@ -0,0 +1,28 @@
+extern crate julia;
+
+use std::io::{self, Cursor};
+
+use julia::api::{Julia, JlIO};
+
+fn main() {
+    let mut jl = Julia::new().unwrap();
+
+    // Julia writes straight into a Rust Write.
+    let out = JlIO::from_writer(io::stdout()).unwrap();
+    let write = jl.base().function("write").unwrap();
+    write.call2(out.inner_ref(), &"Hello from a Rust Write!\n".into())
+        .unwrap();
+    out.close().unwrap();
+
+    // and reads from a Rust Read.
+    let input = Cursor::new(b"first line\nsecond line\n".to_vec());
+    let input = JlIO::from_reader(input).unwrap();
+    let readline = jl.base().function("readline").unwrap();
+    let line = readline.call1(input.inner_ref()).unwrap();
+    println!("readline: {}", line);
+
+    // scripts are evaluated while they're being read.
+    let mut script = Cursor::new("x = 2\nf(y) =\n    x * y\nf(21)\n");
+    let ret = jl.load(&mut script, Some("script.jl")).unwrap();
+    println!("f(21) = {}", ret);
+}
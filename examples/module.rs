@@ -0,0 +1,38 @@
+extern crate julia;
+
+use std::convert::TryFrom;
+
+use julia::api::{Julia, Module, Value};
+use julia::error::Error;
+
+fn main() {
+    let mut jl = Julia::new().unwrap();
+
+    // an isolated namespace for a plugin, reachable as Main.Plugins.Greeter.
+    let plugins = Module::new("Plugins", Some(jl.main())).unwrap();
+    let greeter = Module::new("Greeter", Some(&plugins)).unwrap();
+    greeter.add_standard_imports().unwrap();
+
+    greeter.set_const("greeting", &Value::from("Hello")).unwrap();
+    greeter.export("greeting").unwrap();
+    println!(
+        "Greeter is in Plugins: {}",
+        greeter.is_submodule_of(&plugins).unwrap()
+    );
+
+    jl.main().using(&greeter).unwrap();
+    println!("{}", jl.eval_string("greeting").unwrap());
+    println!("{}", jl.eval_string("Plugins.Greeter.greeting").unwrap());
+
+    // introspection, e.g. for autocompletion.
+    for (name, value) in greeter.bindings().unwrap() {
+        println!("Greeter.{} = {}", String::try_from(&name).unwrap(), value);
+    }
+    println!("greeting is const: {}", greeter.is_const("greeting").unwrap());
+    println!("parent: {}", String::try_from(&greeter.parent().unwrap().name().unwrap()).unwrap());
+
+    match jl.main().global("undefined_variable") {
+        Err(Error::UndefVar(name)) => println!("{} is not defined", name),
+        ret => println!("unexpected: {:?}", ret),
+    }
+}
@@ -0,0 +1,49 @@
+extern crate julia;
+
+use std::convert::TryFrom;
+use std::env;
+use std::fs;
+
+use julia::api::{Julia, Module, Exception};
+use julia::error::Error;
+
+fn main() {
+    let mut jl = Julia::new().unwrap();
+
+    // a local package directory, no network needed.
+    let dir = env::temp_dir().join("julia-rs-packages");
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(
+        dir.join("Greeting.jl"),
+        "module Greeting\nexport greet\ngreet(name) = \"Hello, $name!\"\nend\n",
+    ).unwrap();
+    fs::write(
+        dir.join("Broken.jl"),
+        "module Broken\n\nundefined_function()\nend\n",
+    ).unwrap();
+
+    jl.push_load_path(&dir).unwrap();
+    println!("LOAD_PATH = {:?}", jl.load_path().unwrap());
+
+    let app = Module::new("App", Some(jl.main())).unwrap();
+    let greeting = jl.using_into(&app, "Greeting").unwrap();
+    println!("loaded {}", String::try_from(&greeting.name().unwrap()).unwrap());
+    println!("{}", jl.eval_string("App.greet(\"Rust\")").unwrap());
+
+    match jl.require("Broken") {
+        Ok(_) => println!("Broken loaded?"),
+        Err(Error::UnhandledException(ex)) => {
+            if let Ok(Some(err)) = ex.load_error() {
+                println!("error in {} at line {}: {}", err.file, err.line, err.error);
+            }
+            match ex.root_cause() {
+                Ok(Exception::UndefVar(_)) => println!("root cause: undefined variable"),
+                Ok(cause) => println!("root cause: {}", cause),
+                Err(err) => println!("couldn't decode: {}", err),
+            }
+        }
+        Err(err) => println!("Error: {}", err),
+    }
+
+    let _ = fs::remove_dir_all(&dir);
+}
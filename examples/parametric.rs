@@ -0,0 +1,38 @@
+#[macro_use]
+extern crate julia;
+
+use julia::api::{Julia, Value, JlValue, Symbol, Datatype};
+use julia::api::datatype::{Type, TypeBuilder, TypeVar, UnionAll};
+
+fn sym(name: &str) -> Value {
+    Value::from_value(Symbol::with_name(name).unwrap()).unwrap()
+}
+
+fn main() {
+    let jl = Julia::new().unwrap();
+
+    // struct Point{T<:Real} x::T; y::T end
+    let real = jl.base().global("Real").unwrap();
+    let t = TypeVar::with_upper_bound("T", &real).unwrap();
+    let point = TypeBuilder::new()
+        .name("Point")
+        .typevars(&[t.clone()])
+        .fnames(&jlvec![sym("x"), sym("y")].unwrap())
+        .ftypes(&jlvec![t.to_value::<_, Value>().unwrap(), t.to_value::<_, Value>().unwrap()].unwrap())
+        .build()
+        .unwrap();
+
+    let wrapper = point.wrapper().unwrap();
+    println!("{}", wrapper);
+    jl.main().set("Point", &wrapper.to_value().unwrap()).unwrap();
+
+    // Point{Float64}
+    let float64 = Value::from_value(Datatype::float64()).unwrap();
+    println!("{}", wrapper.apply_type1(&float64).unwrap());
+
+    // Vector{T} where T<:Real
+    let vector = Type::from_value(jl.base().global("Vector").unwrap()).unwrap();
+    let body = vector.apply_type1(&t.to_value().unwrap()).unwrap();
+    let reals = UnionAll::new(&t, &body.to_value().unwrap()).unwrap();
+    println!("{} with T<:{}", reals, reals.var().unwrap().upper_bound().unwrap());
+}
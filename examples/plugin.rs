@@ -0,0 +1,35 @@
+extern crate julia;
+
+use std::env;
+use std::fs;
+
+use julia::api::{Julia, Module, Expr};
+
+fn main() {
+    let jl = Julia::new().unwrap();
+
+    // a plugin script including a helper relative to its own location.
+    let dir = env::temp_dir().join("julia-rs-plugin");
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("helper.jl"), "helper() = \"helped from $(@__DIR__)\"\n").unwrap();
+    fs::write(
+        dir.join("plugin.jl"),
+        "include(\"helper.jl\")\nconst NAME = \"plugin\"\nrun() = \"$NAME: $(helper())\"\n",
+    ).unwrap();
+
+    // every plugin gets its own module, so globals don't clash.
+    let plugin = Module::new("Plugin", Some(jl.main())).unwrap();
+    plugin.add_standard_imports().unwrap();
+    plugin.include(dir.join("plugin.jl")).unwrap();
+
+    println!("{}", plugin.eval_string("run()").unwrap());
+
+    let expr = Expr::with_string("NAME * \"!\"").unwrap();
+    println!("{}", plugin.eval(&expr).unwrap());
+
+    plugin.eval_string("x = 1; y = 2").unwrap();
+    println!("Plugin.y = {}", plugin.global("y").unwrap());
+    println!("Main has y: {}", jl.main().is_defined("y").unwrap());
+
+    let _ = fs::remove_dir_all(&dir);
+}
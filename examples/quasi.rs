@@ -0,0 +1,36 @@
+#[macro_use]
+extern crate julia;
+
+use julia::api::Julia;
+
+fn main() {
+    let jl = Julia::new().unwrap();
+
+    let xs = jl.eval_string("[1.0, 2.0, 3.0, 4.0]").unwrap();
+    let n = 4.0;
+    println!("mean = {}", julia!("sum($xs) / $n", xs, n).unwrap());
+
+    // strings are passed as values, so quotes in them can't break out.
+    let name = "Robert\"); rm(\"-rf";
+    println!("{}", julia!("string(\"Hello, \", $name, \"!\")", name).unwrap());
+
+    // arbitrary Rust expressions, and the code is only parsed once.
+    for i in 1..4 {
+        println!("{}", julia!("$x + length($xs)", x = i * 10, xs).unwrap());
+    }
+
+    // multi-line code, with spacing that matters to Julia.
+    let code = julia!(r#"
+        if $n > 0
+            $xs .* 2
+        else
+            $xs
+        end
+    "#, n, xs);
+    println!("{}", code.unwrap());
+
+    match julia!("undefined_function($n)", n) {
+        Ok(value) => println!("unexpected: {}", value),
+        Err(err) => println!("Error: {}", err),
+    }
+}
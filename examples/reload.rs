@@ -0,0 +1,40 @@
+extern crate julia;
+
+use std::env;
+use std::fs;
+use std::thread;
+use std::time::Duration;
+
+use julia::api::{Julia, Module, Reloader};
+
+fn main() {
+    let mut jl = Julia::new().unwrap();
+
+    let path = env::temp_dir().join("julia-rs-reload.jl");
+    fs::write(&path, "greet() = \"Hello, v1!\"\n").unwrap();
+
+    let app = Module::new("App", Some(jl.main())).unwrap();
+    app.add_standard_imports().unwrap();
+
+    let mut reloader = Reloader::new();
+    reloader.on_reload(|reload| match reload.load_error() {
+        Some(err) => println!("reload failed at {}:{}: {}", err.file, err.line, err.error),
+        None => println!("reloaded {}", reload.path.display()),
+    });
+    reloader.watch(&mut jl, &path, &app).unwrap();
+    println!("{}", app.eval_string("greet()").unwrap());
+
+    // modification times may only have a resolution of a second.
+    thread::sleep(Duration::from_millis(1100));
+    fs::write(&path, "greet() = \"Hello, v2!\"\n").unwrap();
+    reloader.poll(&mut jl);
+    println!("{}", app.eval_string("greet()").unwrap());
+
+    // a syntax error keeps the previous definition.
+    thread::sleep(Duration::from_millis(1100));
+    fs::write(&path, "greet() = \"Hello, v3!\"\nbroken(\n").unwrap();
+    reloader.poll(&mut jl);
+    println!("{}", app.eval_string("greet()").unwrap());
+
+    let _ = fs::remove_file(&path);
+}
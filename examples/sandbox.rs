@@ -0,0 +1,38 @@
+extern crate julia;
+
+use std::time::Duration;
+
+use julia::api::{Julia, Sandbox, Value};
+use julia::error::Error;
+
+fn main() {
+    let jl = Julia::new().unwrap();
+
+    let mut sandbox = Sandbox::new(&jl).unwrap();
+    for name in &["+", "-", "*", "<", "sqrt", "string"] {
+        sandbox.allow_from(jl.base(), name).unwrap();
+    }
+    sandbox.allow("answer", &Value::from(42i64)).unwrap();
+    sandbox.set_timeout(Some(Duration::from_millis(500)));
+
+    let snippets = [
+        "sqrt(answer * 2 + 16)",
+        "f(x) = x * x; f(3) - 1",
+        "string(\"forty-two is \", answer)",
+        "run(`ls`)",
+        "ccall(:getpid, Cint, ())",
+        "Base.Filesystem.rm(\"/tmp/x\")",
+        "Main.secret",
+        "eval(:(1 + 1))",
+        "while true; end",
+    ];
+
+    for snippet in &snippets {
+        match sandbox.eval(snippet) {
+            Ok(value) => println!("{} => {}", snippet, value),
+            Err(Error::Disallowed(what)) => println!("{} => rejected: {}", snippet, what),
+            Err(Error::Timeout) => println!("{} => timed out", snippet),
+            Err(err) => println!("{} => error: {}", snippet, err),
+        }
+    }
+}
@@ -0,0 +1,29 @@
+extern crate julia;
+
+use julia::api::{Julia, JlValue, Function, Task};
+use julia::api::task;
+
+fn main() {
+    let mut jl = Julia::new().unwrap();
+
+    let producer = jl.eval_string("() -> (for i in 1:3; println(\"step \", i); yield(); end; :done)")
+        .unwrap();
+    let producer = Function::from_value(producer).unwrap();
+
+    let t = Task::with_function(&producer).unwrap();
+    println!("state: {:?}", t.state());
+
+    t.schedule().unwrap();
+    while !t.is_done().unwrap() {
+        println!("yielding from {:?}", task::current_task().map(|t| t.is_started()));
+        task::yield_now().unwrap();
+    }
+    println!("result: {}", t.fetch().unwrap());
+
+    let failing = jl.eval_string("() -> error(\"oops\")").unwrap();
+    let failing = Function::from_value(failing).unwrap();
+    let t = Task::with_function(&failing).unwrap();
+    t.schedule().unwrap();
+    let _ = t.wait();
+    println!("failed: {:?}, exception: {:?}", t.is_failed(), t.exception());
+}
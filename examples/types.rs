@@ -0,0 +1,45 @@
+extern crate julia;
+
+use julia::api::{Julia, Value, JlValue, Datatype};
+use julia::api::datatype::{Type, TypeKind};
+
+fn ty(jl: &mut Julia, code: &str) -> Type {
+    Type::from_value(jl.eval_string(code).unwrap()).unwrap()
+}
+
+fn main() {
+    let mut jl = Julia::new().unwrap();
+
+    let int = ty(&mut jl, "Int64");
+    let real = ty(&mut jl, "Real");
+    let maybe = ty(&mut jl, "Union{Int64, Void}");
+    let vectors = ty(&mut jl, "Vector{T} where T<:Real");
+
+    println!("Int64 <: Real: {}", int.is_subtype(&real).unwrap());
+    println!("typejoin(Int64, Float64) = {}", int.typejoin(&ty(&mut jl, "Float64")).unwrap());
+    println!("typeintersect = {}", maybe.typeintersect(&real).unwrap());
+    println!("supertype(Int64) = {}", int.supertype().unwrap());
+    println!("subtypes(Real) = {:?}", real.subtypes().unwrap());
+
+    for t in &[&int, &maybe, &vectors] {
+        match t.kind().unwrap() {
+            TypeKind::Datatype(dt) => println!(
+                "{}: {}.{} with {:?}, concrete: {}, isbits: {}",
+                t,
+                dt.module().unwrap(),
+                dt.name().unwrap(),
+                dt.parameters().unwrap(),
+                dt.is_concrete(),
+                dt.is_isbits()
+            ),
+            TypeKind::Union(_) => println!("{}: a union", t),
+            TypeKind::UnionAll(ua) => println!("{}: where {}", t, ua.var().unwrap()),
+            TypeKind::TypeVar(tv) => println!("{}: a type variable", tv),
+            TypeKind::Bottom => println!("Union{{}}"),
+        }
+    }
+
+    let x = Value::from(5i64);
+    println!("5 isa Real: {}", x.isa(&real).unwrap());
+    println!("5 isa Float64: {}", x.isa(&Datatype::float64()).unwrap());
+}
@@ -0,0 +1,32 @@
+extern crate julia;
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+use julia::api::{Julia, JlValue, WeakValue};
+
+fn main() {
+    let mut jl = Julia::new().unwrap();
+
+    let finalized = Rc::new(Cell::new(false));
+    let weak = {
+        let x = jl.eval_string("Ref(42)").unwrap();
+
+        let flag = finalized.clone();
+        x.add_finalizer_fn(move |x| {
+            println!("finalizing {}", x);
+            flag.set(true);
+        }).unwrap();
+
+        let weak = WeakValue::new(&x).unwrap();
+        println!("alive: {}", weak.is_alive());
+        weak
+    };
+
+    // finalizers run after the collection, the next time Julia gets a chance.
+    jl.gc_mut().collect(true).unwrap();
+    jl.eval_string("yield()").unwrap();
+
+    println!("alive: {}", weak.is_alive());
+    println!("finalized: {}", finalized.get());
+}
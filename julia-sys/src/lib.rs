@@ -700,6 +700,32 @@ mod box_long {
 
 pub use box_long::*;
 
+/// GC counters, as in src/gc.h of Julia 0.6.
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct jl_gc_num_t {
+    pub allocd: i64,
+    pub deferred_alloc: i64,
+    pub freed: i64,
+    pub malloc: u64,
+    pub realloc: u64,
+    pub poolalloc: u64,
+    pub bigalloc: u64,
+    pub freecall: u64,
+    pub total_time: u64,
+    pub total_allocd: u64,
+    pub since_sweep: u64,
+    pub interval: usize,
+    pub pause: libc::c_int,
+    pub full_sweep: libc::c_int,
+}
+
+extern "C" {
+    // Exported, but missing from the headers.
+    pub fn jl_exit_on_sigint(on: libc::c_int);
+    pub fn jl_gc_num() -> jl_gc_num_t;
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,177 @@
+//! Module providing a typed wrapper for the native Julia Channel and bridges
+//! between it and Rust channels.
+
+use std::marker::PhantomData;
+use std::convert::TryFrom;
+use std::sync::mpsc::{Sender, Receiver};
+
+use error::{Result, Error};
+use super::{Value, JlValue, Exception};
+use super::base_function;
+use super::datatype::Type;
+
+/// A Julia `Channel` carrying values convertible to and from `T`.
+pub struct JlChannel<T> {
+    inner: Value,
+    _marker: PhantomData<T>,
+}
+
+impl<T> Clone for JlChannel<T> {
+    fn clone(&self) -> JlChannel<T> {
+        JlChannel {
+            inner: self.inner.clone(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T> JlChannel<T> {
+    /// Creates a new `Channel{Any}` which can hold up to `size` values before
+    /// put! blocks.
+    pub fn new(size: usize) -> Result<JlChannel<T>> {
+        let channel = base_function("Channel")?;
+        let inner = channel.call1(&Value::from(size))?;
+        Ok(JlChannel {
+            inner: inner,
+            _marker: PhantomData,
+        })
+    }
+
+    /// Wraps an existing Julia `Channel`.
+    ///
+    /// ## Errors
+    ///
+    /// Returns Error::InvalidUnbox if `value` is not a Channel.
+    pub fn with_value(value: Value) -> Result<JlChannel<T>> {
+        let channel: Type = base_function("Channel")?.to_value()?;
+        if !value.isa(&channel)? {
+            return Err(Error::InvalidUnbox);
+        }
+        Ok(JlChannel {
+            inner: value,
+            _marker: PhantomData,
+        })
+    }
+
+    /// Immutably borrows the inner Value.
+    pub fn inner_ref(&self) -> &Value {
+        &self.inner
+    }
+
+    /// Consumes self and returns the inner Value.
+    pub fn into_inner(self) -> Value {
+        self.inner
+    }
+
+    /// Closes the Channel. Pending and future take!-s on an empty closed
+    /// Channel fail.
+    pub fn close(&self) -> Result<()> {
+        let close = base_function("close")?;
+        close.call1(&self.inner)?;
+        Ok(())
+    }
+
+    /// Checks if the Channel is open.
+    pub fn is_open(&self) -> Result<bool> {
+        let isopen = base_function("isopen")?;
+        let p = isopen.call1(&self.inner)?;
+        bool::try_from(&p)
+    }
+
+    /// Checks if a value can be taken without blocking.
+    pub fn is_ready(&self) -> Result<bool> {
+        let isready = base_function("isready")?;
+        let p = isready.call1(&self.inner)?;
+        bool::try_from(&p)
+    }
+}
+
+impl<T: Into<Value>> JlChannel<T> {
+    /// Puts a value into the Channel, blocking the current Task if it's full.
+    pub fn put(&self, x: T) -> Result<()> {
+        let put = base_function("put!")?;
+        put.call2(&self.inner, &x.into())?;
+        Ok(())
+    }
+
+    /// Puts every value received from a Rust Receiver into the Channel.
+    ///
+    /// Closes the Channel once all the Senders are dropped. Returns early if
+    /// the Channel was closed from the Julia side, dropping the Receiver, so
+    /// that the Senders notice too.
+    pub fn feed(&self, rx: Receiver<T>) -> Result<()> {
+        for x in rx {
+            match self.put(x) {
+                Ok(()) => (),
+                Err(Error::UnhandledException(Exception::InvalidState(_))) => return Ok(()),
+                Err(err) => return Err(err),
+            }
+        }
+        self.close()
+    }
+}
+
+impl<T> JlChannel<T>
+where
+    T: for<'a> TryFrom<&'a Value, Error = Error>,
+{
+    /// Takes a value from the Channel, blocking the current Task if it's
+    /// empty.
+    pub fn take(&self) -> Result<T> {
+        let take = base_function("take!")?;
+        let x = take.call1(&self.inner)?;
+        T::try_from(&x)
+    }
+
+    /// Returns an iterator taking values until the Channel is closed and
+    /// empty.
+    pub fn iter(&self) -> Iter<T> {
+        Iter { channel: self }
+    }
+
+    /// Sends every value taken from the Channel to a Rust Sender.
+    ///
+    /// Returns once the Channel is closed and empty. If the Receiver is
+    /// dropped, the Channel is closed.
+    pub fn forward(&self, tx: Sender<T>) -> Result<()> {
+        for x in self.iter() {
+            if tx.send(x?).is_err() {
+                return self.close();
+            }
+        }
+        Ok(())
+    }
+}
+
+/// An iterator taking values from a JlChannel until it's closed and empty.
+pub struct Iter<'a, T: 'a> {
+    channel: &'a JlChannel<T>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T>
+where
+    T: for<'b> TryFrom<&'b Value, Error = Error>,
+{
+    type Item = Result<T>;
+
+    fn next(&mut self) -> Option<Result<T>> {
+        match self.channel.take() {
+            // take! throws an InvalidStateException once the channel is closed
+            // and there's nothing more to take.
+            Err(Error::UnhandledException(Exception::InvalidState(_))) => None,
+            x => Some(x),
+        }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a JlChannel<T>
+where
+    T: for<'b> TryFrom<&'b Value, Error = Error>,
+{
+    type Item = Result<T>;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
@@ -0,0 +1,84 @@
+//! Module providing introspection of lowered and compiled code, the same
+//! information `@code_lowered`, `@code_typed`, `@code_llvm` and
+//! `@code_native` show in the Julia REPL.
+//!
+//! Argument types are passed as values, e.g.
+//! `Value::from_value(Datatype::float64())?`, and the methods matching them
+//! are looked up like for a call with arguments of those types.
+
+use std::convert::TryFrom;
+
+use sys::*;
+use error::{Result, Error};
+use super::{Value, JlValue, Function, Array, Symbol, Type};
+use super::glue;
+
+jlvalues! {
+    pub struct CodeInfo(jl_code_info_t);
+}
+
+impl CodeInfo {
+    /// Returns the statements of the code.
+    pub fn code(&self) -> Result<Vec<Value>> {
+        let raw = self.lock()?;
+        Array::new(unsafe { (*raw).code })?.as_vec()
+    }
+
+    /// Returns the names of the arguments and local variables, starting
+    /// with `#self#` for the function itself.
+    pub fn slot_names(&self) -> Result<Vec<String>> {
+        let raw = self.lock()?;
+        Array::new(unsafe { (*raw).slotnames })?
+            .as_vec()?
+            .into_iter()
+            .map(|name| String::try_from(&Symbol::from_value(name)?))
+            .collect()
+    }
+
+    /// Checks if type inference has run on the code.
+    pub fn is_inferred(&self) -> Result<bool> {
+        let raw = self.lock()?;
+        Ok(unsafe { (*raw).inferred } != 0)
+    }
+}
+
+impl Function {
+    /// Returns the inferred code of the methods matching the argument types
+    /// along with their inferred return types.
+    pub fn code_typed(&self, types: &[Value]) -> Result<Vec<(CodeInfo, Type)>> {
+        let code_typed = glue::function("code_typed")?;
+        let this: Value = self.to_value()?;
+        let ret = code_typed.call(Some(&this).into_iter().chain(types))?;
+
+        let mut typed = vec![];
+        for pair in Array::from_value(ret)?.as_vec()? {
+            let raw = pair.lock()?;
+            let code = Value::new(unsafe { jl_get_nth_field(raw, 0) })?;
+            let rettype = Value::new(unsafe { jl_get_nth_field(raw, 1) })?;
+            if !code.is_code_info() {
+                return Err(Error::InvalidUnbox);
+            }
+            typed.push((CodeInfo::from_value(code)?, Type::from_value(rettype)?));
+        }
+        Ok(typed)
+    }
+
+    /// Returns the LLVM IR generated for the method matching the argument
+    /// types.
+    pub fn code_llvm(&self, types: &[Value]) -> Result<String> {
+        self.reflect("code_llvm", types)
+    }
+
+    /// Returns the assembly generated for the method matching the argument
+    /// types.
+    pub fn code_native(&self, types: &[Value]) -> Result<String> {
+        self.reflect("code_native", types)
+    }
+
+    fn reflect(&self, name: &str, types: &[Value]) -> Result<String> {
+        let reflect = glue::function(name)?;
+        let this: Value = self.to_value()?;
+        let ret = reflect.call(Some(&this).into_iter().chain(types))?;
+        String::try_from(&ret)
+    }
+}
@@ -7,7 +7,9 @@ use std::convert::TryFrom;
 
 use sys::*;
 use error::{Result, Error};
-use api::{Value, JlValue, IntoSymbol, Array, Svec};
+use api::{Value, JlValue, IntoSymbol, Array, Svec, Symbol, Module};
+use api::glue;
+use api::base_function;
 
 #[derive(Clone, Copy, Hash, PartialEq, Debug)]
 pub enum VarargKind {
@@ -30,14 +32,41 @@ impl TryFrom<u32> for VarargKind {
     }
 }
 
+/// A type of any kind, see `Type::kind`.
+#[derive(Clone, Debug)]
+pub enum TypeKind {
+    /// A type with a name, e.g. `Int64`, `Vector{Int64}` or `Real`.
+    Datatype(Datatype),
+    /// A union of types, e.g. `Union{Int64, Void}`.
+    Union(Union),
+    /// A type with free parameters, e.g. `Vector{T} where T<:Real`.
+    UnionAll(UnionAll),
+    /// A type variable, e.g. the `T` above.
+    TypeVar(TypeVar),
+    /// The empty union `Union{}`, a subtype of every type.
+    Bottom,
+}
+
 jlvalues! {
     pub struct Type(jl_value_t);
     pub struct Datatype(jl_datatype_t);
     pub struct Union(jl_uniontype_t);
     pub struct UnionAll(jl_unionall_t);
     pub struct Tuple(jl_tupletype_t);
+    pub struct TypeVar(jl_tvar_t);
+    pub struct TypeName(jl_typename_t);
 }
 
+/// Implemented by the handles to types, i.e. what values can be instances
+/// of, see `Value::isa`.
+pub trait JlType<T>: JlValue<T> {}
+
+impl JlType<jl_value_t> for Type {}
+impl JlType<jl_datatype_t> for Datatype {}
+impl JlType<jl_uniontype_t> for Union {}
+impl JlType<jl_unionall_t> for UnionAll {}
+impl JlType<jl_tupletype_t> for Tuple {}
+
 impl Type {
     /// Creates a new Julia array of this type.
     pub fn new_array<I>(&self, params: I) -> Result<Array>
@@ -99,6 +128,79 @@ impl Type {
         Type::new(raw)
     }
 
+    /// Returns what kind of type this is.
+    ///
+    /// ## Errors
+    ///
+    /// Returns Error::InvalidUnbox if the value isn't a type.
+    pub fn kind(&self) -> Result<TypeKind> {
+        let raw = self.lock()?;
+        unsafe {
+            if raw == jl_bottom_type {
+                Ok(TypeKind::Bottom)
+            } else if jl_is_datatype(raw) {
+                JlValue::new(raw as *mut _).map(TypeKind::Datatype)
+            } else if jl_is_uniontype(raw) {
+                JlValue::new(raw as *mut _).map(TypeKind::Union)
+            } else if jl_is_unionall(raw) {
+                JlValue::new(raw as *mut _).map(TypeKind::UnionAll)
+            } else if jl_is_typevar(raw) {
+                JlValue::new(raw as *mut _).map(TypeKind::TypeVar)
+            } else {
+                Err(Error::InvalidUnbox)
+            }
+        }
+    }
+
+    /// Checks if this is a subtype of `other`, i.e. `self <: other`.
+    pub fn is_subtype(&self, other: &Type) -> Result<bool> {
+        let p = unsafe { jl_subtype(self.lock()?, other.lock()?) };
+        jl_catch!();
+        Ok(p != 0)
+    }
+
+    /// Checks if both types are subtypes of each other.
+    pub fn is_equal(&self, other: &Type) -> Result<bool> {
+        let p = unsafe { jl_types_equal(self.lock()?, other.lock()?) };
+        jl_catch!();
+        Ok(p != 0)
+    }
+
+    /// Returns the most specific type both types are subtypes of.
+    pub fn typejoin(&self, other: &Type) -> Result<Type> {
+        let typejoin = base_function("typejoin")?;
+        let ret = typejoin.call2(&self.to_value()?, &other.to_value()?)?;
+        Type::from_value(ret)
+    }
+
+    /// Returns a type containing all values of both types, or `Union{}` if
+    /// there are none.
+    pub fn typeintersect(&self, other: &Type) -> Result<Type> {
+        let raw = unsafe { jl_type_intersection(self.lock()?, other.lock()?) };
+        jl_catch!();
+        Type::new(raw)
+    }
+
+    /// Returns the supertype, e.g. `Signed` for `Int64`, or `Any` for `Any`
+    /// itself.
+    pub fn supertype(&self) -> Result<Type> {
+        let supertype = base_function("supertype")?;
+        let ret = supertype.call1(&self.to_value()?)?;
+        Type::from_value(ret)
+    }
+
+    /// Returns the direct subtypes of an abstract type which are currently
+    /// defined.
+    pub fn subtypes(&self) -> Result<Vec<Type>> {
+        let subtypes = glue::function("subtypes")?;
+        let ret = subtypes.call1(&self.to_value()?)?;
+        Array::from_value(ret)?
+            .as_vec()?
+            .into_iter()
+            .map(Type::from_value)
+            .collect()
+    }
+
     /// Applies function to the inner pointer.
     pub fn map<T, F>(&self, f: F) -> Result<T>
     where
@@ -299,6 +401,63 @@ impl Datatype {
     }
 }
 
+impl Datatype {
+    /// Returns the name of the type, shared by all of its instantiations.
+    pub fn type_name(&self) -> Result<TypeName> {
+        let raw = self.lock()?;
+        TypeName::new(unsafe { (*raw).name })
+    }
+
+    /// Returns the type with all of its parameters, e.g. `Point` for
+    /// `Point{Float64}`, which can be instantiated with `apply_type`.
+    pub fn wrapper(&self) -> Result<Type> {
+        self.type_name()?.wrapper()
+    }
+
+    /// Returns the name, e.g. `"Array"` for `Array{Int64,1}`.
+    pub fn name(&self) -> Result<String> {
+        String::try_from(&self.type_name()?.name()?)
+    }
+
+    /// Returns the module the type is defined in.
+    pub fn module(&self) -> Result<Module> {
+        self.type_name()?.module()
+    }
+
+    /// Returns the parameters, e.g. `Int64` and `1` for `Array{Int64,1}`.
+    pub fn parameters(&self) -> Result<Vec<Value>> {
+        let raw = self.lock()?;
+        Svec::new(unsafe { (*raw).parameters })?.as_vec()
+    }
+
+    /// Returns the supertype, e.g. `Signed` for `Int64`.
+    pub fn supertype(&self) -> Result<Datatype> {
+        let raw = self.lock()?;
+        Datatype::new(unsafe { (*raw).super_ })
+    }
+
+    /// Checks if the type can have instances, i.e. it's neither abstract nor
+    /// has free parameters.
+    pub fn is_concrete(&self) -> bool {
+        self.map_or(|v| unsafe { jl_is_leaf_type(v as *mut _) != 0 }, false)
+    }
+
+    /// Checks if instances of the type are plain data, i.e. immutable and
+    /// without references to other objects.
+    pub fn is_isbits(&self) -> bool {
+        self.map_or(|v| unsafe { jl_isbits(v) }, false)
+    }
+
+    /// Applies function to the inner pointer and returns a default value if
+    /// its poisoned.
+    pub fn map_or<T, F>(&self, f: F, optb: T) -> T
+    where
+        F: FnOnce(*mut jl_datatype_t) -> T,
+    {
+        self.lock().map(f).unwrap_or(optb)
+    }
+}
+
 impl Default for Datatype {
     fn default() -> Datatype {
         Datatype::any()
@@ -357,6 +516,106 @@ impl UnionAll {
     }
 }
 
+impl UnionAll {
+    /// Creates the type `body where tv`, e.g. `Vector{T} where T<:Real`.
+    ///
+    /// ## Errors
+    ///
+    /// Returns Error::InvalidUnbox if `body` doesn't refer to `tv`, since
+    /// the result would be `body` itself.
+    pub fn new(tv: &TypeVar, body: &Value) -> Result<UnionAll> {
+        let raw = unsafe { jl_type_unionall(tv.lock()?, body.lock()?) };
+        jl_catch!();
+        let ret = Value::new(raw)?;
+        if !ret.is_unionall() {
+            return Err(Error::InvalidUnbox);
+        }
+        UnionAll::from_value(ret)
+    }
+
+    /// Returns the type variable.
+    pub fn var(&self) -> Result<TypeVar> {
+        let raw = self.lock()?;
+        JlValue::new(unsafe { (*raw).var })
+    }
+
+    /// Returns the type the variable is used in.
+    pub fn body(&self) -> Result<Type> {
+        let raw = self.lock()?;
+        Type::new(unsafe { (*raw).body })
+    }
+}
+
+impl TypeVar {
+    /// Creates a type variable with a lower and an upper bound, like
+    /// `TypeVar(name, lb, ub)` in Julia.
+    pub fn new<S: IntoSymbol>(name: S, lb: &Value, ub: &Value) -> Result<TypeVar> {
+        let name = name.into_symbol()?;
+        let raw = unsafe { jl_new_typevar(name.lock()?, lb.lock()?, ub.lock()?) };
+        jl_catch!();
+        JlValue::new(raw)
+    }
+
+    /// Creates a type variable with an upper bound, e.g. `T<:Real`.
+    pub fn with_upper_bound<S: IntoSymbol>(name: S, ub: &Value) -> Result<TypeVar> {
+        let bottom = unsafe { Value::new_unchecked(jl_bottom_type) };
+        TypeVar::new(name, &bottom, ub)
+    }
+
+    /// Creates a type variable without bounds.
+    pub fn with_name<S: IntoSymbol>(name: S) -> Result<TypeVar> {
+        let any = Value::from_value(Datatype::any())?;
+        TypeVar::with_upper_bound(name, &any)
+    }
+
+    /// Returns the name of the variable.
+    pub fn name(&self) -> Result<Symbol> {
+        let raw = self.lock()?;
+        Symbol::new(unsafe { (*raw).name })
+    }
+
+    /// Returns the lower bound.
+    pub fn lower_bound(&self) -> Result<Type> {
+        let raw = self.lock()?;
+        Type::new(unsafe { (*raw).lb })
+    }
+
+    /// Returns the upper bound.
+    pub fn upper_bound(&self) -> Result<Type> {
+        let raw = self.lock()?;
+        Type::new(unsafe { (*raw).ub })
+    }
+}
+
+impl TypeName {
+    /// Creates a new type name in the current module.
+    pub fn with_name<S: IntoSymbol>(name: S) -> Result<TypeName> {
+        let name = name.into_symbol()?;
+        let raw = unsafe { jl_new_typename(name.lock()?) };
+        jl_catch!();
+        JlValue::new(raw)
+    }
+
+    /// Returns the name.
+    pub fn name(&self) -> Result<Symbol> {
+        let raw = self.lock()?;
+        Symbol::new(unsafe { (*raw).name })
+    }
+
+    /// Returns the module the type is defined in.
+    pub fn module(&self) -> Result<Module> {
+        let raw = self.lock()?;
+        JlValue::new(unsafe { (*raw).module })
+    }
+
+    /// Returns the type with all of its parameters, or the only
+    /// instantiation of the type if it has none.
+    pub fn wrapper(&self) -> Result<Type> {
+        let raw = self.lock()?;
+        Type::new(unsafe { (*raw).wrapper })
+    }
+}
+
 impl Tuple {
     pub fn apply(params: &Svec) -> Result<Tuple> {
         let params = params.lock()?;
@@ -473,6 +732,7 @@ impl TypeBuilder {
         self
     }
 
+    /// Sets the parameters, usually type variables.
     pub fn params(mut self, params: &Svec) -> TypeBuilder {
         self.params = match params.lock() {
             Ok(params) => params,
@@ -484,6 +744,25 @@ impl TypeBuilder {
         self
     }
 
+    /// Sets the parameters to type variables, e.g. `T` for `Point{T<:Real}`.
+    /// The fields may then use them in their types.
+    pub fn typevars(mut self, typevars: &[TypeVar]) -> TypeBuilder {
+        let params = unsafe { jl_alloc_svec(typevars.len()) };
+        for (i, tv) in typevars.iter().enumerate() {
+            match tv.lock() {
+                Ok(tv) => unsafe {
+                    jl_svecset(params, i, tv);
+                },
+                Err(err) => {
+                    self.err = Some(err);
+                    return self;
+                }
+            }
+        }
+        self.params = params;
+        self
+    }
+
     /// Sets the names of the fields.
     pub fn fnames(mut self, fnames: &Svec) -> TypeBuilder {
         self.fnames = match fnames.lock() {
@@ -0,0 +1,89 @@
+//! Module providing access to the libuv event loop which drives Julia's
+//! Timers, IO and `@async` Tasks.
+//!
+//! Julia only processes events while Julia code is running, so an embedding
+//! application which idles in Rust has to pump the loop itself. Either call
+//! `process_events` periodically, or register `event_fd` with the host's
+//! own poller (e.g. mio or tokio) and call `process_events` whenever it
+//! becomes readable or `event_timeout` elapses.
+
+use std::time::Duration;
+use std::convert::TryFrom;
+#[cfg(unix)]
+use std::os::unix::io::RawFd;
+
+use sys::*;
+use error::Result;
+use super::Julia;
+use super::base_function;
+use super::task;
+
+impl Julia {
+    /// Runs a single iteration of the event loop without blocking and then
+    /// lets the Tasks it woke up run.
+    ///
+    /// Returns true if the loop still has active handles or requests, i.e.
+    /// if there's more work to be expected in the future.
+    pub fn process_events(&mut self) -> Result<bool> {
+        let alive = unsafe { jl_process_events(jl_global_event_loop()) };
+        jl_catch!();
+        task::yield_now()?;
+        Ok(alive != 0)
+    }
+
+    /// Processes events and runs scheduled Tasks until there's nothing left
+    /// to do right away.
+    ///
+    /// Doesn't wait for future events such as pending Timers, see
+    /// `event_timeout` for when the next one is due.
+    pub fn run_until_idle(&mut self) -> Result<()> {
+        loop {
+            self.process_events()?;
+            if !self.has_ready_work()? {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Checks if any Tasks are scheduled to run or any events are ready to be
+    /// processed.
+    pub(crate) fn has_ready_work(&self) -> Result<bool> {
+        if self.event_timeout() == Some(Duration::from_millis(0)) {
+            return Ok(true);
+        }
+
+        let workqueue = self.base().global("Workqueue")?;
+        let isempty = base_function("isempty")?;
+        let empty = isempty.call1(&workqueue)?;
+        Ok(!bool::try_from(&empty)?)
+    }
+
+    /// Checks if the event loop has any active handles or requests.
+    pub fn is_event_loop_alive(&self) -> bool {
+        unsafe { uv_loop_alive(jl_global_event_loop()) != 0 }
+    }
+
+    /// Returns the file descriptor of the event loop's backend (epoll, kqueue,
+    /// ...), which becomes readable when there are events to process.
+    ///
+    /// Returns None on platforms without such a descriptor.
+    #[cfg(unix)]
+    pub fn event_fd(&self) -> Option<RawFd> {
+        let fd = unsafe { uv_backend_fd(jl_global_event_loop()) };
+        if fd < 0 { None } else { Some(fd as RawFd) }
+    }
+
+    /// Returns how long the host may wait before `process_events` has to be
+    /// called again, e.g. because a Timer is due.
+    ///
+    /// Returns None if there's no deadline and only `event_fd` has to be
+    /// watched.
+    pub fn event_timeout(&self) -> Option<Duration> {
+        let timeout = unsafe { uv_backend_timeout(jl_global_event_loop()) };
+        if timeout < 0 {
+            None
+        } else {
+            Some(Duration::from_millis(timeout as u64))
+        }
+    }
+}
@@ -3,6 +3,7 @@
 
 use std::fmt;
 use std::error;
+use std::convert::TryFrom;
 use std::ops::Deref;
 use std::ops::DerefMut;
 
@@ -212,6 +213,50 @@ impl Exception {
     }
 }
 
+/// The location and cause of a LoadError.
+#[derive(Clone)]
+pub struct LoadError {
+    /// The file being loaded.
+    pub file: String,
+    /// The line the error occurred at.
+    pub line: usize,
+    /// The underlying error.
+    pub error: Exception,
+}
+
+impl Exception {
+    /// Decodes a LoadError into the file and line it occurred at and the
+    /// underlying error. Returns None for other exceptions.
+    pub fn load_error(&self) -> Result<Option<LoadError>> {
+        let value = match *self {
+            Exception::Load(ref value) => value,
+            _ => return Ok(None),
+        };
+
+        let file = String::try_from(&value.get("file")?)?;
+        let line = i64::try_from(&value.get("line")?)?;
+        let error = Exception::with_value(value.get("error")?)?;
+        Ok(Some(LoadError {
+            file: file,
+            line: line as usize,
+            error: error,
+        }))
+    }
+
+    /// Returns the underlying error, unwrapping any LoadErrors and
+    /// InitErrors.
+    pub fn root_cause(&self) -> Result<Exception> {
+        match *self {
+            Exception::Load(ref value) |
+            Exception::Init(ref value) => {
+                let error = Exception::with_value(value.get("error")?)?;
+                error.root_cause()
+            }
+            _ => Ok(self.clone()),
+        }
+    }
+}
+
 impl Deref for Exception {
     type Target = Value;
     fn deref(&self) -> &Value {
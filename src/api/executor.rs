@@ -0,0 +1,655 @@
+//! Module providing a thread-safe handle to a Julia runtime which lives on its
+//! own dedicated thread.
+//!
+//! Julia has to be driven from the thread it was initialized in and Values
+//! are not Send, so instead of sharing the runtime, closures are sent to the
+//! Julia thread and their results are sent back, either synchronously or as
+//! Futures.
+
+use std::any::Any;
+use std::panic::{self, AssertUnwindSafe};
+use std::result;
+use std::convert::TryFrom;
+use std::collections::VecDeque;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll, Waker};
+use std::time::Duration;
+use std::sync::{Arc, Mutex};
+use std::sync::mpsc::{self, Sender, Receiver, RecvTimeoutError};
+use std::thread::{self, JoinHandle};
+
+use error::{Result, Error};
+use super::{Julia, Value, JlValue, Function, Module, Task, JlChannel};
+
+/// How long the Julia thread waits for new messages at most before processing
+/// events and checking on pending Tasks again, unless a Timer is due earlier.
+const IDLE_INTERVAL_MS: u64 = 10;
+
+/// A closure sent to the Julia thread.
+type Job = Box<dyn FnOnce(&mut Julia, &mut Worker) + Send>;
+
+/// A closure polled on the Julia thread until it returns true.
+type Poller = Box<dyn FnMut(&mut Julia) -> bool>;
+
+/// State of the Julia thread that isn't the runtime itself.
+struct Worker {
+    pending: Vec<Poller>,
+}
+
+impl Worker {
+    /// Processes events, lets scheduled Julia Tasks run and checks which
+    /// pending Tasks have finished.
+    fn poll(&mut self, jl: &mut Julia) {
+        // an exception thrown here belongs to no one in particular.
+        let _ = jl.process_events();
+
+        self.pending.retain_mut(|poller| !poller(jl));
+    }
+
+    /// Returns how long to wait for new messages before polling again, or
+    /// None if there's nothing to poll and the thread may block.
+    ///
+    /// New messages end the wait early, so pending Tasks only need to be
+    /// checked on once Julia has something to do.
+    fn wait_time(&self, jl: &Julia) -> Option<Duration> {
+        if self.pending.is_empty() && !jl.is_event_loop_alive() {
+            return None;
+        }
+
+        // an error here belongs to no one, so it's treated as nothing ready.
+        if jl.has_ready_work().unwrap_or(false) {
+            return Some(Duration::from_millis(0));
+        }
+        let idle = Duration::from_millis(IDLE_INTERVAL_MS);
+        Some(jl.event_timeout().map_or(idle, |timeout| timeout.min(idle)))
+    }
+}
+
+/// Messages understood by the Julia thread.
+enum Message {
+    /// Run a closure with the runtime.
+    Run(Job),
+    /// Stop the loop and exit Julia with a status.
+    Exit(i32),
+}
+
+/// An Error which is guaranteed not to contain any Julia values and therefore
+/// can be safely sent from the Julia thread.
+pub(crate) struct Detached(Error);
+
+// SAFETY: `new` replaces the only variant holding a Julia value, an
+// exception, with its string representation on the Julia thread, so a
+// Detached never holds anything that's dereferenced off the Julia thread.
+unsafe impl Send for Detached {}
+
+impl Detached {
+    /// Detaches an Error from the Julia thread, replacing exceptions with their
+    /// string representation.
+    pub(crate) fn new(err: Error) -> Detached {
+        match err {
+            Error::UnhandledException(ex) => {
+                let string = ex.inner_ref().to_string();
+                Detached(Error::DetachedException(string))
+            }
+            err => Detached(err),
+        }
+    }
+
+    /// Returns the inner Error.
+    pub(crate) fn into_inner(self) -> Error {
+        self.0
+    }
+}
+
+/// Runs a closure sent to the Julia thread, turning a panic into an error
+/// instead of unwinding through the thread's loop.
+fn catch_panic<T, F: FnOnce() -> Result<T>>(f: F) -> Result<T> {
+    panic::catch_unwind(AssertUnwindSafe(f)).unwrap_or_else(|payload| {
+        Err(Error::Panicked(panic_message(&*payload)))
+    })
+}
+
+/// Returns the message a panic was started with, if it's a string.
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(msg) = payload.downcast_ref::<&str>() {
+        msg.to_string()
+    } else if let Some(msg) = payload.downcast_ref::<String>() {
+        msg.clone()
+    } else {
+        "Box<Any>".to_owned()
+    }
+}
+
+/// State shared by every clone of a JuliaExecutor.
+struct Inner {
+    sender: Mutex<Option<Sender<Message>>>,
+    thread: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl Inner {
+    fn send(&self, msg: Message) -> Result<()> {
+        let sender = self.sender.lock()?;
+        match *sender {
+            Some(ref sender) => sender.send(msg).map_err(|_| Error::ExecutorClosed),
+            None => Err(Error::ExecutorClosed),
+        }
+    }
+
+    fn exit(&self, status: i32) -> Result<()> {
+        let sender = self.sender.lock()?.take();
+        if let Some(sender) = sender {
+            // the thread might have already died, in which case there's
+            // nothing left to stop.
+            let _ = sender.send(Message::Exit(status));
+        }
+
+        let thread = self.thread.lock()?.take();
+        if let Some(thread) = thread {
+            thread.join().map_err(|_| Error::ExecutorClosed)?;
+        }
+        Ok(())
+    }
+}
+
+impl Drop for Inner {
+    fn drop(&mut self) {
+        let _ = self.exit(0);
+    }
+}
+
+/// The loop running on the Julia thread.
+fn main_loop(receiver: Receiver<Message>, init: Sender<result::Result<(), Detached>>) {
+    let mut jl = match Julia::new() {
+        Ok(jl) => jl,
+        Err(err) => {
+            let _ = init.send(Err(Detached::new(err)));
+            return;
+        }
+    };
+    let _ = init.send(Ok(()));
+
+    let mut worker = Worker { pending: vec![] };
+
+    loop {
+        let msg = match worker.wait_time(&jl) {
+            None => {
+                match receiver.recv() {
+                    Ok(msg) => Some(msg),
+                    Err(_) => break,
+                }
+            }
+            Some(timeout) => {
+                match receiver.recv_timeout(timeout) {
+                    Ok(msg) => Some(msg),
+                    Err(RecvTimeoutError::Timeout) => None,
+                    Err(RecvTimeoutError::Disconnected) => break,
+                }
+            }
+        };
+
+        match msg {
+            Some(Message::Run(job)) => job(&mut jl, &mut worker),
+            Some(Message::Exit(status)) => {
+                jl.at_exit(Some(status));
+                break;
+            }
+            None => (),
+        }
+
+        worker.poll(&mut jl);
+    }
+
+    // jl is dropped here, calling the at_exit hook.
+}
+
+/// A cloneable, Send and Sync handle to a Julia runtime running on a
+/// dedicated thread.
+///
+/// The runtime is shut down when `shutdown` or `exit` is called or when the
+/// last handle is dropped.
+///
+/// ## Example
+///
+/// ```
+/// use julia::api::JuliaExecutor;
+///
+/// let jl = JuliaExecutor::new().unwrap();
+/// let x: f64 = jl.eval("sqrt(2.0)").unwrap();
+/// jl.shutdown().unwrap();
+/// ```
+#[derive(Clone)]
+pub struct JuliaExecutor {
+    inner: Arc<Inner>,
+}
+
+impl JuliaExecutor {
+    /// Spawns a new thread and initializes Julia in it.
+    ///
+    /// ## Errors
+    ///
+    /// Returns the error that occurred while initializing Julia, e.g.
+    /// Error::JuliaInitialized if Julia is already running in this process.
+    pub fn new() -> Result<JuliaExecutor> {
+        let (sender, receiver) = mpsc::channel();
+        let (init_tx, init_rx) = mpsc::channel();
+
+        let thread = thread::Builder::new()
+            .name("julia".to_owned())
+            .spawn(move || main_loop(receiver, init_tx))?;
+
+        let init = init_rx.recv().map_err(|_| Error::ExecutorClosed)?;
+        if let Err(err) = init {
+            let _ = thread.join();
+            return Err(err.into_inner());
+        }
+
+        Ok(JuliaExecutor {
+            inner: Arc::new(Inner {
+                sender: Mutex::new(Some(sender)),
+                thread: Mutex::new(Some(thread)),
+            }),
+        })
+    }
+
+    /// Checks if the Julia thread is still accepting work.
+    pub fn is_running(&self) -> bool {
+        self.inner
+            .sender
+            .lock()
+            .map(|sender| sender.is_some())
+            .unwrap_or(false)
+    }
+
+    /// Runs a closure on the Julia thread and blocks until it returns.
+    ///
+    /// Exceptions thrown by Julia are returned as Error::DetachedException,
+    /// since they can't leave the Julia thread.
+    ///
+    /// ## Errors
+    ///
+    /// Returns Error::ExecutorClosed if the runtime was shut down and
+    /// Error::Panicked if the closure panicked. The runtime keeps running
+    /// after a panic.
+    pub fn run<F, T>(&self, f: F) -> Result<T>
+    where
+        F: FnOnce(&mut Julia) -> Result<T> + Send + 'static,
+        T: Send + 'static,
+    {
+        let (tx, rx) = mpsc::channel();
+        self.inner.send(Message::Run(Box::new(move |jl: &mut Julia, _: &mut Worker| {
+            let _ = tx.send(catch_panic(|| f(jl)).map_err(Detached::new));
+        })))?;
+
+        rx.recv()
+            .map_err(|_| Error::ExecutorClosed)?
+            .map_err(Detached::into_inner)
+    }
+
+    /// Parses and evaluates a string on the Julia thread and converts the
+    /// result into a Rust type.
+    pub fn eval<S, T>(&self, expr: S) -> Result<T>
+    where
+        S: Into<String>,
+        T: for<'a> TryFrom<&'a Value, Error = Error> + Send + 'static,
+    {
+        let expr = expr.into();
+        self.run(move |jl| {
+            let value = jl.eval_string(expr)?;
+            T::try_from(&value)
+        })
+    }
+
+    /// Runs a closure on the Julia thread without blocking. The returned
+    /// Future resolves when the closure returns.
+    pub fn spawn<F, T>(&self, f: F) -> JuliaFuture<T>
+    where
+        F: FnOnce(&mut Julia) -> Result<T> + Send + 'static,
+        T: Send + 'static,
+    {
+        let (completer, future) = future();
+        // if sending fails, the completer is dropped and the future resolves
+        // to Error::ExecutorClosed.
+        let _ = self.inner.send(Message::Run(Box::new(move |jl: &mut Julia, _: &mut Worker| {
+            completer.complete(catch_panic(|| f(jl)));
+        })));
+        future
+    }
+
+    /// Parses and evaluates a string on the Julia thread without blocking.
+    pub fn eval_async<S, T>(&self, expr: S) -> JuliaFuture<T>
+    where
+        S: Into<String>,
+        T: for<'a> TryFrom<&'a Value, Error = Error> + Send + 'static,
+    {
+        let expr = expr.into();
+        self.spawn(move |jl| {
+            let value = jl.eval_string(expr)?;
+            T::try_from(&value)
+        })
+    }
+
+    /// Calls a function on the Julia thread without blocking. The function is
+    /// looked up by its path relative to Main, e.g. `"Base.sqrt"`.
+    pub fn call_async<S, A, T>(&self, function: S, args: Vec<A>) -> JuliaFuture<T>
+    where
+        S: Into<String>,
+        A: Into<Value> + Send + 'static,
+        T: for<'a> TryFrom<&'a Value, Error = Error> + Send + 'static,
+    {
+        let function = function.into();
+        self.spawn(move |jl| {
+            let function = resolve_function(jl.main(), &function)?;
+            let args: Vec<Value> = args.into_iter().map(Into::into).collect();
+            let value = function.call(&args)?;
+            T::try_from(&value)
+        })
+    }
+
+    /// Creates a Task on the Julia thread and returns a Future which resolves
+    /// to the result of the Task once it's done.
+    ///
+    /// The Task has to be scheduled by the closure. While it's running, the
+    /// Julia thread keeps handling other requests, yielding to Julia between
+    /// them.
+    pub fn task_async<F, T>(&self, f: F) -> JuliaFuture<T>
+    where
+        F: FnOnce(&mut Julia) -> Result<Task> + Send + 'static,
+        T: for<'a> TryFrom<&'a Value, Error = Error> + Send + 'static,
+    {
+        let (completer, future) = future();
+        let _ = self.inner.send(Message::Run(Box::new(move |jl: &mut Julia, worker: &mut Worker| {
+            let task = match catch_panic(|| f(jl)) {
+                Ok(task) => task,
+                Err(err) => return completer.complete(Err(err)),
+            };
+
+            let mut completer = Some(completer);
+            worker.pending.push(Box::new(move |_: &mut Julia| {
+                match task_result(&task) {
+                    None => false,
+                    Some(result) => {
+                        if let Some(completer) = completer.take() {
+                            completer.complete(result.and_then(|value| T::try_from(&value)));
+                        }
+                        true
+                    }
+                }
+            }));
+        })));
+        future
+    }
+
+    /// Creates a Channel on the Julia thread and returns a stream of the values
+    /// put into it.
+    ///
+    /// Values are taken as soon as they're ready, without blocking the Julia
+    /// thread. The stream ends when the Channel is closed and empty. Dropping
+    /// the stream closes the Channel.
+    pub fn stream<F, T>(&self, f: F) -> ChannelStream<T>
+    where
+        F: FnOnce(&mut Julia) -> Result<JlChannel<T>> + Send + 'static,
+        T: for<'a> TryFrom<&'a Value, Error = Error> + Send + 'static,
+    {
+        let shared = Arc::new(Mutex::new(StreamShared {
+            queue: VecDeque::new(),
+            waker: None,
+            done: false,
+            dropped: false,
+        }));
+        let stream = ChannelStream { shared: shared.clone() };
+
+        let sent = self.inner.send(Message::Run(Box::new(move |jl: &mut Julia, worker: &mut Worker| {
+            let channel = match catch_panic(|| f(jl)) {
+                Ok(channel) => channel,
+                Err(err) => {
+                    if let Ok(mut shared) = shared.lock() {
+                        shared.queue.push_back(Err(Detached::new(err)));
+                        shared.finish();
+                    }
+                    return;
+                }
+            };
+
+            worker.pending.push(Box::new(move |_: &mut Julia| {
+                let mut shared = match shared.lock() {
+                    Ok(shared) => shared,
+                    Err(_) => return true,
+                };
+                shared.pump(&channel);
+                shared.done
+            }));
+        })));
+
+        if sent.is_err() {
+            if let Ok(mut shared) = stream.shared.lock() {
+                shared.queue.push_back(Err(Detached(Error::ExecutorClosed)));
+                shared.finish();
+            }
+        }
+        stream
+    }
+
+    /// Stops the Julia thread, waits for it to finish and calls the Julia
+    /// at_exit hook with status 0.
+    ///
+    /// Affects all clones of this handle.
+    pub fn shutdown(&self) -> Result<()> {
+        self.exit(0)
+    }
+
+    /// Stops the Julia thread, waits for it to finish and calls the Julia
+    /// at_exit hook with `status`.
+    ///
+    /// Affects all clones of this handle.
+    pub fn exit(&self, status: i32) -> Result<()> {
+        self.inner.exit(status)
+    }
+}
+
+/// Looks up a function by a dot-separated path relative to `module`.
+fn resolve_function(module: &Module, path: &str) -> Result<Function> {
+    let mut names = path.split('.').collect::<Vec<_>>();
+    let name = names.pop().ok_or(Error::InvalidSymbol)?;
+
+    let mut module = module.clone();
+    for name in names {
+        module = Module::from_value(module.global(name)?)?;
+    }
+    module.function(name)
+}
+
+/// Returns None if the task isn't done yet, otherwise its result or the
+/// exception it failed with.
+fn task_result(task: &Task) -> Option<Result<Value>> {
+    match task.is_done() {
+        Ok(false) => None,
+        // wait returns immediately for a finished task and rethrows its
+        // exception if it failed.
+        Ok(true) => Some(task.wait()),
+        Err(err) => Some(Err(err)),
+    }
+}
+
+/// State shared by a JuliaFuture and its Completer.
+struct Shared<T> {
+    result: Option<result::Result<T, Detached>>,
+    waker: Option<Waker>,
+    closed: bool,
+}
+
+/// Creates a connected Completer and JuliaFuture.
+fn future<T>() -> (Completer<T>, JuliaFuture<T>) {
+    let shared = Arc::new(Mutex::new(Shared {
+        result: None,
+        waker: None,
+        closed: false,
+    }));
+    (Completer { shared: shared.clone() }, JuliaFuture { shared: shared })
+}
+
+/// The Julia side of a JuliaFuture. Dropping it without completing resolves
+/// the future to Error::ExecutorClosed.
+struct Completer<T> {
+    shared: Arc<Mutex<Shared<T>>>,
+}
+
+impl<T> Completer<T> {
+    fn complete(self, result: Result<T>) {
+        if let Ok(mut shared) = self.shared.lock() {
+            shared.result = Some(result.map_err(Detached::new));
+        }
+        // waking happens in drop.
+    }
+}
+
+impl<T> Drop for Completer<T> {
+    fn drop(&mut self) {
+        if let Ok(mut shared) = self.shared.lock() {
+            shared.closed = true;
+            if let Some(waker) = shared.waker.take() {
+                waker.wake();
+            }
+        }
+    }
+}
+
+/// A Future resolved when the Julia thread finishes a piece of work.
+///
+/// Works with any executor, e.g. tokio or async-std, and never blocks the
+/// thread polling it.
+pub struct JuliaFuture<T> {
+    shared: Arc<Mutex<Shared<T>>>,
+}
+
+impl<T> Future for JuliaFuture<T> {
+    type Output = Result<T>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<T>> {
+        let mut shared = match self.shared.lock() {
+            Ok(shared) => shared,
+            Err(err) => return Poll::Ready(Err(From::from(err))),
+        };
+
+        if let Some(result) = shared.result.take() {
+            Poll::Ready(result.map_err(Detached::into_inner))
+        } else if shared.closed {
+            Poll::Ready(Err(Error::ExecutorClosed))
+        } else {
+            shared.waker = Some(cx.waker().clone());
+            Poll::Pending
+        }
+    }
+}
+
+/// State shared by a ChannelStream and the Julia thread.
+struct StreamShared<T> {
+    queue: VecDeque<result::Result<T, Detached>>,
+    waker: Option<Waker>,
+    done: bool,
+    dropped: bool,
+}
+
+impl<T> StreamShared<T>
+where
+    T: for<'a> TryFrom<&'a Value, Error = Error>,
+{
+    /// Takes all values that are ready from the channel without blocking.
+    fn pump(&mut self, channel: &JlChannel<T>) {
+        if self.dropped {
+            let _ = channel.close();
+            self.done = true;
+            return;
+        }
+
+        loop {
+            match channel.is_ready() {
+                Ok(true) => self.queue.push_back(channel.take().map_err(Detached::new)),
+                Ok(false) => break,
+                Err(err) => {
+                    self.queue.push_back(Err(Detached::new(err)));
+                    return self.finish();
+                }
+            }
+        }
+
+        match channel.is_open() {
+            Ok(true) => self.wake_if_ready(),
+            Ok(false) => self.finish(),
+            Err(err) => {
+                self.queue.push_back(Err(Detached::new(err)));
+                self.finish();
+            }
+        }
+    }
+}
+
+impl<T> StreamShared<T> {
+    fn finish(&mut self) {
+        self.done = true;
+        self.wake_if_ready();
+    }
+
+    fn wake_if_ready(&mut self) {
+        if !self.queue.is_empty() || self.done {
+            if let Some(waker) = self.waker.take() {
+                waker.wake();
+            }
+        }
+    }
+}
+
+/// A stream of values taken from a Julia Channel, see
+/// `JuliaExecutor::stream`.
+///
+/// Mirrors the `Stream` trait of the futures crate with `poll_next`, and can be
+/// consumed with `while let Some(x) = stream.next().await`.
+pub struct ChannelStream<T> {
+    shared: Arc<Mutex<StreamShared<T>>>,
+}
+
+impl<T> ChannelStream<T> {
+    /// Attempts to pull out the next value. Returns `Poll::Ready(None)` once
+    /// the Channel is closed and empty.
+    pub fn poll_next(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Result<T>>> {
+        let mut shared = match self.shared.lock() {
+            Ok(shared) => shared,
+            Err(err) => return Poll::Ready(Some(Err(From::from(err)))),
+        };
+
+        if let Some(x) = shared.queue.pop_front() {
+            Poll::Ready(Some(x.map_err(Detached::into_inner)))
+        } else if shared.done {
+            Poll::Ready(None)
+        } else {
+            shared.waker = Some(cx.waker().clone());
+            Poll::Pending
+        }
+    }
+
+    /// Returns a Future resolving to the next value.
+    pub fn next(&mut self) -> Next<T> {
+        Next { stream: self }
+    }
+}
+
+impl<T> Drop for ChannelStream<T> {
+    fn drop(&mut self) {
+        if let Ok(mut shared) = self.shared.lock() {
+            shared.dropped = true;
+        }
+    }
+}
+
+/// A Future resolving to the next value of a ChannelStream.
+pub struct Next<'a, T: 'a> {
+    stream: &'a mut ChannelStream<T>,
+}
+
+impl<'a, T> Future for Next<'a, T> {
+    type Output = Option<Result<T>>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Result<T>>> {
+        Pin::new(&mut *self.stream).poll_next(cx)
+    }
+}
@@ -0,0 +1,216 @@
+//! Module providing construction and inspection of Julia expressions, i.e.
+//! abstract syntax trees.
+//!
+//! Expressions can be built from Rust instead of formatting and parsing
+//! source code, e.g. `x + 1` is
+//!
+//! ```ignore
+//! Expr::call("+", &[Value::from_value(Symbol::with_name("x")?)?, Value::from(1i64)])?
+//! ```
+
+use std::convert::TryFrom;
+
+use sys::*;
+use error::{Result, Error};
+use super::{Value, JlValue, Function, Symbol, IntoSymbol, Module};
+use super::parse::parse;
+use super::code::CodeInfo;
+use super::glue;
+pub use super::value::Expr;
+
+impl Expr {
+    /// Creates an expression with a head, e.g. `:call` or `:block`, and
+    /// arguments, like `Expr(head, args...)` in Julia.
+    pub fn new<S: IntoSymbol>(head: S, args: &[Value]) -> Result<Expr> {
+        let head = Value::from_value(head.into_symbol()?)?;
+        let constructor: Function = unsafe { JlValue::new(jl_expr_type as *mut _)? };
+
+        let ret = constructor.call(Some(&head).into_iter().chain(args))?;
+        Expr::from_value(ret)
+    }
+
+    /// Creates a call to the function named `f`.
+    pub fn call<S: IntoSymbol>(f: S, args: &[Value]) -> Result<Expr> {
+        let mut call_args = Vec::with_capacity(args.len() + 1);
+        call_args.push(Value::from_value(f.into_symbol()?)?);
+        call_args.extend(args.iter().cloned());
+        Expr::new("call", &call_args)
+    }
+
+    /// Parses a string consisting of a single expression without evaluating
+    /// it. See `api::parse` for finding out why parsing failed.
+    ///
+    /// ## Errors
+    ///
+    /// Returns Error::Syntax if the string isn't a single valid expression
+    /// and Error::InvalidUnbox if it's a literal or a name instead.
+    pub fn with_string(string: &str) -> Result<Expr> {
+        let value = parse(string)?.into_result()?;
+        if !value.is_expr() {
+            return Err(Error::InvalidUnbox);
+        }
+        Expr::from_value(value)
+    }
+
+    /// Returns the head of the expression, e.g. `:call`.
+    pub fn head(&self) -> Result<Symbol> {
+        let raw = self.lock()?;
+        let head = unsafe { (*raw).head };
+        Symbol::new(head)
+    }
+
+    /// Returns the name of the head of the expression, e.g. `"call"`.
+    pub fn head_name(&self) -> Result<String> {
+        String::try_from(&self.head()?)
+    }
+
+    /// Returns the number of arguments.
+    pub fn nargs(&self) -> Result<usize> {
+        let raw = self.lock()?;
+        Ok(unsafe { jl_expr_nargs(raw) })
+    }
+
+    /// Returns the argument at index `i`.
+    ///
+    /// ## Errors
+    ///
+    /// Returns Error::NullPointer if `i` is out of bounds.
+    pub fn arg(&self, i: usize) -> Result<Value> {
+        if i >= self.nargs()? {
+            return Err(Error::NullPointer);
+        }
+        let raw = self.lock()?;
+        Value::new(unsafe { jl_exprarg(raw, i) })
+    }
+
+    /// Replaces the argument at index `i`.
+    ///
+    /// ## Errors
+    ///
+    /// Returns Error::NullPointer if `i` is out of bounds.
+    pub fn set_arg(&self, i: usize, value: &Value) -> Result<()> {
+        if i >= self.nargs()? {
+            return Err(Error::NullPointer);
+        }
+        let raw = self.lock()?;
+        unsafe {
+            jl_exprargset(raw, i, value.lock()?);
+        }
+        Ok(())
+    }
+
+    /// Returns the arguments of the expression.
+    pub fn args(&self) -> Result<Vec<Value>> {
+        let raw = self.lock()?;
+        let nargs = unsafe { jl_expr_nargs(raw) };
+        (0..nargs)
+            .map(|i| Value::new(unsafe { jl_exprarg(raw, i) }))
+            .collect()
+    }
+
+    /// Visits this expression and everything in it, see `ExprVisitor`.
+    pub fn walk<V: ExprVisitor + ?Sized>(&self, visitor: &mut V) -> Result<()> {
+        if !visitor.visit_expr(self)? {
+            return Ok(());
+        }
+        for arg in self.args()? {
+            walk_value(&arg, visitor)?;
+        }
+        Ok(())
+    }
+
+    /// Evaluate expression.
+    pub fn expand(&self) -> Result<Value> {
+        let raw = self.lock()?;
+        let raw = unsafe { jl_expand(raw as *mut _) };
+        jl_catch!();
+        Value::new(raw)
+    }
+
+    /// Expands the macro calls in the expression as if it was evaluated in
+    /// `module`, including the ones the macros expand to.
+    pub fn macroexpand(&self, module: &Module) -> Result<Value> {
+        let macroexpand = glue::function("macroexpand")?;
+        let module: Value = module.to_value()?;
+        let this: Value = self.to_value()?;
+        macroexpand.call2(&module, &this)
+    }
+
+    /// Lowers the expression as if it was evaluated in `module`, returning
+    /// the code that's actually run, or None if it doesn't need lowering,
+    /// e.g. a block with only a name in it.
+    pub fn lower(&self, module: &Module) -> Result<Option<CodeInfo>> {
+        let lower = glue::function("lower")?;
+        let module: Value = module.to_value()?;
+        let this: Value = self.to_value()?;
+        let lowered = lower.call2(&module, &this)?;
+        if lowered.is_nothing() {
+            Ok(None)
+        } else if lowered.is_code_info() {
+            CodeInfo::from_value(lowered).map(Some)
+        } else {
+            Err(Error::InvalidUnbox)
+        }
+    }
+}
+
+/// A visitor over the nodes of an expression, called by `Expr::walk` in
+/// depth-first order.
+///
+/// Every method does nothing by default, so implementations only need to
+/// override the ones for the nodes they're interested in.
+pub trait ExprVisitor {
+    /// Called for every expression before its arguments are visited. Return
+    /// false to skip the arguments.
+    fn visit_expr(&mut self, _expr: &Expr) -> Result<bool> {
+        Ok(true)
+    }
+
+    /// Called for every symbol, i.e. name.
+    fn visit_symbol(&mut self, _sym: &Symbol) -> Result<()> {
+        Ok(())
+    }
+
+    /// Called for everything else, e.g. literals, QuoteNodes, GlobalRefs and
+    /// LineNumberNodes.
+    fn visit_value(&mut self, _value: &Value) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Visits a value which may or may not be an expression, e.g. the result of
+/// parsing a string, see `Expr::walk`.
+pub fn walk_value<V: ExprVisitor + ?Sized>(value: &Value, visitor: &mut V) -> Result<()> {
+    if value.is_expr() {
+        value.to_value::<_, Expr>()?.walk(visitor)
+    } else if value.is_symbol() {
+        visitor.visit_symbol(&value.to_value()?)
+    } else {
+        visitor.visit_value(value)
+    }
+}
+
+/// Creates a QuoteNode, which evaluates to `value` itself, e.g. a quoted
+/// symbol.
+pub fn quote_node(value: &Value) -> Result<Value> {
+    let mut args = [value.lock()?];
+    let raw = unsafe { jl_new_structv(jl_quotenode_type, args.as_mut_ptr(), 1) };
+    jl_catch!();
+    Value::new(raw)
+}
+
+/// Creates a LineNumberNode, which sets the line of the following
+/// expressions in backtraces.
+pub fn line_number_node(line: usize) -> Result<Value> {
+    let constructor: Function = unsafe { JlValue::new(jl_linenumbernode_type as *mut _)? };
+    constructor.call1(&Value::from(line as isize))
+}
+
+/// Creates a GlobalRef, which refers to a global of a specific module, e.g.
+/// `Base.sqrt`, regardless of the module the expression is evaluated in.
+pub fn global_ref<S: IntoSymbol>(module: &Module, name: S) -> Result<Value> {
+    let name = name.into_symbol()?;
+    let raw = unsafe { jl_module_globalref(module.lock()?, name.lock()?) };
+    jl_catch!();
+    Value::new(raw)
+}
@@ -5,7 +5,8 @@ use smallvec::SmallVec;
 
 use sys::*;
 use error::{Result, Error};
-use super::{JlValue, Value};
+use super::{JlValue, Value, Exception};
+use super::output;
 
 jlvalues! {
     pub struct Function(jl_function_t);
@@ -23,35 +24,30 @@ impl Function {
         }
 
         let ret = unsafe { jl_call(self.lock()?, argv.as_mut_ptr(), argv.len() as i32) };
-        jl_catch!();
-        Value::new(ret).map_err(|_| Error::CallError)
+        output::finish(Exception::catch(), ret, Error::CallError)
     }
 
     /// Call with 0 Value-s.
     pub fn call0(&self) -> Result<Value> {
         let ret = unsafe { jl_call0(self.lock()?) };
-        jl_catch!();
-        Value::new(ret).map_err(|_| Error::CallError)
+        output::finish(Exception::catch(), ret, Error::CallError)
     }
 
     /// Call with 1 Value.
     pub fn call1(&self, arg1: &Value) -> Result<Value> {
         let ret = unsafe { jl_call1(self.lock()?, arg1.lock()?) };
-        jl_catch!();
-        Value::new(ret).map_err(|_| Error::CallError)
+        output::finish(Exception::catch(), ret, Error::CallError)
     }
 
     /// Call with 2 Value-s.
     pub fn call2(&self, arg1: &Value, arg2: &Value) -> Result<Value> {
         let ret = unsafe { jl_call2(self.lock()?, arg1.lock()?, arg2.lock()?) };
-        jl_catch!();
-        Value::new(ret).map_err(|_| Error::CallError)
+        output::finish(Exception::catch(), ret, Error::CallError)
     }
 
     /// Call with 3 Value-s.
     pub fn call3(&self, arg1: &Value, arg2: &Value, arg3: &Value) -> Result<Value> {
         let ret = unsafe { jl_call3(self.lock()?, arg1.lock()?, arg2.lock()?, arg3.lock()?) };
-        jl_catch!();
-        Value::new(ret).map_err(|_| Error::CallError)
+        output::finish(Exception::catch(), ret, Error::CallError)
     }
 }
@@ -0,0 +1,287 @@
+//! Module providing statistics about and finer control over the Julia
+//! garbage collector.
+//!
+//! Julia 0.6 has no callbacks for the start and end of a collection, so
+//! collections can only be noticed after the fact, by comparing snapshots of
+//! the counters, see `GcMonitor`.
+
+use std::time::Duration;
+use std::panic::{self, AssertUnwindSafe};
+
+use libc::c_void;
+
+use sys::*;
+use error::Result;
+use super::{Gc, Value, JlValue};
+use super::glue;
+
+/// A snapshot of the garbage collector's counters.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct GcStats {
+    /// Allocation counter of the current collection cycle in bytes. Julia
+    /// resets it to minus the collection interval at every collection and
+    /// collects once it's positive, so it's only meaningful together with
+    /// `total_allocd`, see `total_bytes`.
+    pub allocd: i64,
+    /// Bytes freed by the last collection.
+    pub freed: i64,
+    /// Number of calls to malloc.
+    pub malloc: u64,
+    /// Number of calls to realloc.
+    pub realloc: u64,
+    /// Number of allocations of small objects from pools.
+    pub pool_alloc: u64,
+    /// Number of allocations of big objects.
+    pub big_alloc: u64,
+    /// Number of calls to free.
+    pub free_call: u64,
+    /// Total time spent collecting garbage.
+    pub total_time: Duration,
+    /// Total bytes allocated up to the last collection.
+    pub total_allocd: u64,
+    /// Number of collections.
+    pub collections: u32,
+    /// Number of full collections.
+    pub full_collections: u32,
+}
+
+impl GcStats {
+    /// Total bytes allocated, like `Base.gc_bytes()`.
+    pub fn total_bytes(&self) -> i64 {
+        (self.total_allocd as i64).wrapping_add(self.allocd)
+    }
+
+    /// Total number of allocations.
+    pub fn allocations(&self) -> u64 {
+        self.malloc + self.realloc + self.pool_alloc + self.big_alloc
+    }
+
+    /// Number of incremental, i.e. not full, collections.
+    pub fn incremental_collections(&self) -> u32 {
+        self.collections.saturating_sub(self.full_collections)
+    }
+
+    /// Returns the difference between this snapshot and an earlier one, i.e.
+    /// what happened in between. The bytes allocated in between are its
+    /// `total_bytes`.
+    ///
+    /// Counters that went down, e.g. because the snapshots were passed in the
+    /// wrong order, are zero, except for `allocd` and `freed`, which may be
+    /// negative.
+    pub fn since(&self, earlier: &GcStats) -> GcStats {
+        GcStats {
+            allocd: self.allocd.saturating_sub(earlier.allocd),
+            freed: self.freed.saturating_sub(earlier.freed),
+            malloc: self.malloc.saturating_sub(earlier.malloc),
+            realloc: self.realloc.saturating_sub(earlier.realloc),
+            pool_alloc: self.pool_alloc.saturating_sub(earlier.pool_alloc),
+            big_alloc: self.big_alloc.saturating_sub(earlier.big_alloc),
+            free_call: self.free_call.saturating_sub(earlier.free_call),
+            total_time: self.total_time.checked_sub(earlier.total_time).unwrap_or_default(),
+            total_allocd: self.total_allocd.saturating_sub(earlier.total_allocd),
+            collections: self.collections.saturating_sub(earlier.collections),
+            full_collections: self.full_collections.saturating_sub(earlier.full_collections),
+        }
+    }
+}
+
+/// Polls the garbage collector for the collections since the last poll, e.g.
+/// to correlate latency spikes with them.
+#[derive(Clone, Debug)]
+pub struct GcMonitor {
+    last: GcStats,
+}
+
+impl GcMonitor {
+    /// Creates a monitor starting from the current counters.
+    pub fn new(gc: &Gc) -> GcMonitor {
+        GcMonitor { last: gc.stats() }
+    }
+
+    /// Returns what happened since the last poll if there were any
+    /// collections in between. Its `collections` are the new collections and
+    /// its `total_time` is the time they paused the program for.
+    pub fn poll(&mut self, gc: &Gc) -> Option<GcStats> {
+        self.update(gc.stats())
+    }
+
+    fn update(&mut self, stats: GcStats) -> Option<GcStats> {
+        let diff = stats.since(&self.last);
+        self.last = stats;
+        if diff.collections > 0 { Some(diff) } else { None }
+    }
+}
+
+/// RAII guard which keeps the garbage collector disabled and restores its
+/// previous state on drop.
+pub struct GcDisabledGuard {
+    was_enabled: bool,
+}
+
+impl Drop for GcDisabledGuard {
+    fn drop(&mut self) {
+        unsafe {
+            jl_gc_enable(self.was_enabled as i32);
+        }
+    }
+}
+
+impl Gc {
+    /// Returns a snapshot of the garbage collector's counters.
+    pub fn stats(&self) -> GcStats {
+        let num = unsafe { jl_gc_num() };
+        GcStats {
+            allocd: num.allocd + num.deferred_alloc,
+            freed: num.freed,
+            malloc: num.malloc,
+            realloc: num.realloc,
+            pool_alloc: num.poolalloc,
+            big_alloc: num.bigalloc,
+            free_call: num.freecall,
+            total_time: Duration::from_nanos(num.total_time),
+            total_allocd: num.total_allocd,
+            collections: num.pause as u32,
+            full_collections: num.full_sweep as u32,
+        }
+    }
+
+    /// Disables the garbage collector until the returned guard is dropped.
+    pub fn disable(&mut self) -> GcDisabledGuard {
+        let was_enabled = unsafe { jl_gc_enable(0) != 0 };
+        GcDisabledGuard { was_enabled: was_enabled }
+    }
+}
+
+/// A Rust closure run as a finalizer.
+type Finalizer = Box<dyn FnOnce(Value)>;
+
+extern "C" fn call_finalizer(handle: *mut c_void, value: *mut jl_value_t) {
+    let f = unsafe { Box::from_raw(handle as *mut Finalizer) };
+    if let Ok(value) = Value::new(value) {
+        let _ = panic::catch_unwind(AssertUnwindSafe(move || f(value)));
+    }
+}
+
+/// Registers a Rust closure as a finalizer of value, see
+/// `JlValue::add_finalizer_fn`.
+pub(crate) fn add_finalizer_fn(value: &Value, f: Finalizer) -> Result<()> {
+    let add_finalizer = glue::function("add_finalizer")?;
+    let handle = Box::into_raw(Box::new(f));
+    let ret = add_finalizer.call3(
+        value,
+        &Value::from(handle as usize),
+        &Value::from(call_finalizer as usize),
+    );
+
+    if let Err(err) = ret {
+        drop(unsafe { Box::from_raw(handle) });
+        return Err(err);
+    }
+    Ok(())
+}
+
+/// A weak reference to a Julia object, which doesn't prevent it from being
+/// collected.
+pub struct WeakValue {
+    inner: Value,
+}
+
+impl WeakValue {
+    /// Creates a weak reference to value.
+    pub fn new(value: &Value) -> Result<WeakValue> {
+        let raw = unsafe { jl_gc_new_weakref(value.lock()?) };
+        jl_catch!();
+        let inner = Value::new(raw as *mut jl_value_t)?;
+
+        // the WeakRef itself is only referenced from Rust.
+        let root = glue::function("root")?;
+        root.call1(&inner)?;
+        Ok(WeakValue { inner: inner })
+    }
+
+    /// Returns the object if it wasn't collected yet.
+    pub fn upgrade(&self) -> Option<Value> {
+        let raw = self.inner.lock().ok()? as *mut jl_weakref_t;
+        let value = unsafe { (*raw).value };
+        let value = Value::new(value).ok()?;
+        if value.is_nothing() { None } else { Some(value) }
+    }
+
+    /// Checks if the object wasn't collected yet.
+    pub fn is_alive(&self) -> bool {
+        self.upgrade().is_some()
+    }
+}
+
+impl Drop for WeakValue {
+    fn drop(&mut self) {
+        if let Ok(unroot) = glue::function("unroot") {
+            let _ = unroot.call1(&self.inner);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{GcStats, GcMonitor};
+
+    fn stats(allocd: i64, total_allocd: u64, n: u64, collections: u32) -> GcStats {
+        GcStats {
+            allocd: allocd,
+            freed: 0,
+            malloc: n,
+            realloc: n,
+            pool_alloc: n,
+            big_alloc: n,
+            free_call: n,
+            total_time: Duration::from_millis(n),
+            total_allocd: total_allocd,
+            collections: collections,
+            full_collections: collections / 2,
+        }
+    }
+
+    #[test]
+    fn since() {
+        // a collection in between resets allocd to minus the interval.
+        let earlier = stats(-100, 1000, 10, 2);
+        let later = stats(-400, 1500, 25, 5);
+
+        let diff = later.since(&earlier);
+        assert_eq!(diff.malloc, 15);
+        assert_eq!(diff.allocations(), 60);
+        assert_eq!(diff.total_time, Duration::from_millis(15));
+        assert_eq!(diff.collections, 3);
+        assert_eq!(diff.full_collections, 1);
+        assert_eq!(diff.incremental_collections(), 2);
+        assert_eq!(diff.total_bytes(), later.total_bytes() - earlier.total_bytes());
+    }
+
+    #[test]
+    fn since_wrong_order() {
+        let earlier = stats(-100, 1000, 10, 2);
+        let later = stats(-400, 1500, 25, 5);
+
+        let diff = earlier.since(&later);
+        assert_eq!(diff.malloc, 0);
+        assert_eq!(diff.allocations(), 0);
+        assert_eq!(diff.total_time, Duration::from_millis(0));
+        assert_eq!(diff.total_allocd, 0);
+        assert_eq!(diff.collections, 0);
+        assert_eq!(diff.incremental_collections(), 0);
+        assert_eq!(diff.allocd, 300);
+    }
+
+    #[test]
+    fn monitor() {
+        let mut monitor = GcMonitor { last: stats(-100, 1000, 10, 2) };
+
+        assert_eq!(monitor.update(stats(-50, 1000, 12, 2)), None);
+        let diff = monitor.update(stats(-400, 1500, 25, 5)).unwrap();
+        assert_eq!(diff.collections, 3);
+        assert_eq!(diff.total_time, Duration::from_millis(13));
+        assert_eq!(monitor.update(stats(-400, 1500, 25, 5)), None);
+    }
+}
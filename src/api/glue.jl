@@ -0,0 +1,285 @@
+# Julia side of julia-rs, defined lazily in Main as JuliaRs.
+#
+# Like the rest of julia-rs, this targets Julia 0.6.
+
+module JuliaRs
+
+# An IO backed by a Rust Read and/or Write. The callbacks are Rust functions
+# taking `handle` as their first argument, see src/api/io.rs.
+mutable struct RustIO <: IO
+    handle::Ptr{Void}
+    read::Ptr{Void}
+    write::Ptr{Void}
+    flush::Ptr{Void}
+    eof::Ptr{Void}
+    close::Ptr{Void}
+    readable::Bool
+    writable::Bool
+    open::Bool
+end
+
+function RustIO(handle::UInt, read::UInt, write::UInt, flush::UInt, eof::UInt,
+                close::UInt, free::UInt, readable::Bool, writable::Bool)
+    io = RustIO(Ptr{Void}(handle), Ptr{Void}(read), Ptr{Void}(write),
+                Ptr{Void}(flush), Ptr{Void}(eof), Ptr{Void}(close),
+                readable, writable, true)
+    free = Ptr{Void}(free)
+    finalizer(io, io -> ccall(free, Void, (Ptr{Void},), io.handle))
+    io
+end
+
+function check(io::RustIO, ret, op)
+    ret < 0 && error("couldn't $op the Rust stream")
+    ret
+end
+
+Base.isopen(io::RustIO) = io.open
+Base.isreadable(io::RustIO) = io.open && io.readable
+Base.iswritable(io::RustIO) = io.open && io.writable
+
+function Base.unsafe_write(io::RustIO, p::Ptr{UInt8}, n::UInt)
+    ret = ccall(io.write, Int, (Ptr{Void}, Ptr{UInt8}, UInt), io.handle, p, n)
+    Int(check(io, ret, "write to"))
+end
+
+function Base.write(io::RustIO, x::UInt8)
+    ret = ccall(io.write, Int, (Ptr{Void}, Ref{UInt8}, UInt), io.handle, x, 1)
+    Int(check(io, ret, "write to"))
+end
+
+function Base.unsafe_read(io::RustIO, p::Ptr{UInt8}, n::UInt)
+    ret = ccall(io.read, Int, (Ptr{Void}, Ptr{UInt8}, UInt), io.handle, p, n)
+    check(io, ret, "read from") < n && throw(EOFError())
+    nothing
+end
+
+function Base.read(io::RustIO, ::Type{UInt8})
+    x = Ref{UInt8}(0)
+    ret = ccall(io.read, Int, (Ptr{Void}, Ref{UInt8}, UInt), io.handle, x, 1)
+    check(io, ret, "read from") < 1 && throw(EOFError())
+    x[]
+end
+
+function Base.eof(io::RustIO)
+    ret = ccall(io.eof, Cint, (Ptr{Void},), io.handle)
+    check(io, ret, "read from") != 0
+end
+
+function Base.flush(io::RustIO)
+    ret = ccall(io.flush, Cint, (Ptr{Void},), io.handle)
+    check(io, ret, "flush")
+    nothing
+end
+
+function Base.close(io::RustIO)
+    if io.open
+        io.open = false
+        ret = ccall(io.close, Cint, (Ptr{Void},), io.handle)
+        check(io, ret, "close")
+    end
+    nothing
+end
+
+# Objects referenced only from Rust, which the GC would otherwise collect,
+# with the number of Rust references to each.
+const ROOTS = ObjectIdDict()
+
+root(x) = (ROOTS[x] = get(ROOTS, x, 0) + 1; nothing)
+
+function unroot(x)
+    n = get(ROOTS, x, 0) - 1
+    if n > 0
+        ROOTS[x] = n
+    else
+        delete!(ROOTS, x)
+    end
+    nothing
+end
+
+# Adds a finalizer calling the Rust closure behind handle.
+function add_finalizer(x, handle::UInt, call::UInt)
+    handle = Ptr{Void}(handle)
+    call = Ptr{Void}(call)
+    finalizer(x, x -> ccall(call, Void, (Ptr{Void}, Any), handle, x))
+    nothing
+end
+
+# Loads a package, like `import name`, and returns its module.
+function require(name::Symbol)
+    Base.require(name)
+    getfield(Main, name)
+end
+
+# Evaluates the file at path in mod, like `include` inside of mod.
+function include(mod::Module, path::AbstractString)
+    # include_from_node1 evaluates in the current module and sets the source
+    # path for @__FILE__, @__DIR__ and relative includes.
+    eval(mod, :(Base.include_from_node1($path)))
+end
+
+# Parses the file at path, throwing a LoadError for the first syntax error, so
+# a file can be checked before any of it is evaluated.
+function check_syntax(path::AbstractString)
+    path = String(path)
+    ex = Base.parse_input_line(String(read(path)), filename=path)
+    line = 1
+    for arg in (isa(ex, Expr) && ex.head === :toplevel ? ex.args : Any[ex])
+        if isa(arg, LineNumberNode)
+            line = arg.line
+        elseif isa(arg, Expr) && arg.head === :line
+            line = arg.args[1]
+        elseif isa(arg, Expr) && (arg.head === :error || arg.head === :incomplete)
+            throw(LoadError(path, line, ParseError(string(arg.args[1]))))
+        end
+    end
+    nothing
+end
+
+# Turns code with interpolations, e.g. `sum($xs) / $n`, into an anonymous
+# function taking the values of the named arguments in order, see the julia!
+# macro.
+function quasi(ex, names::AbstractString...)
+    for arg in (isa(ex, Expr) && ex.head === :toplevel ? ex.args : Any[ex])
+        if isa(arg, Expr) && (arg.head === :error || arg.head === :incomplete)
+            throw(ParseError(string(arg.args[1])))
+        end
+    end
+
+    args = Dict(Symbol(name) => gensym(name) for name in names)
+    body = interpolate(ex, args)
+    if isa(body, Expr) && body.head === :toplevel
+        body = Expr(:block, body.args...)
+    end
+    params = [args[Symbol(name)] for name in names]
+    eval(Main, Expr(:->, Expr(:tuple, params...), body))
+end
+
+function interpolate(ex, args)
+    if isa(ex, Expr) && ex.head === :$
+        name = ex.args[1]
+        if !isa(name, Symbol)
+            error("only names of arguments can be interpolated, not ", ex)
+        elseif !haskey(args, name)
+            error("\$", name, " isn't an argument of julia!")
+        end
+        args[name]
+    elseif isa(ex, Expr) && ex.head !== :quote
+        Expr(ex.head, map(arg -> interpolate(arg, args), ex.args)...)
+    else
+        ex
+    end
+end
+
+# Expands all the macros in ex as if it was evaluated in mod.
+macroexpand(mod::Module, ex) = eval(mod, :(Base.macroexpand($(QuoteNode(ex)))))
+
+# Lowers ex as if it was evaluated in mod and returns its CodeInfo, or nothing
+# if it doesn't need lowering, e.g. a name or a literal.
+function lower(mod::Module, ex)
+    lowered = eval(mod, :(Base.expand($(QuoteNode(ex)))))
+    if isa(lowered, Expr) && lowered.head === :error
+        error(string(lowered.args[1]))
+    elseif isa(lowered, Expr) && lowered.head === :thunk
+        lowered.args[1]
+    else
+        nothing
+    end
+end
+
+# The inferred code of the methods of f matching the argument types, as
+# (CodeInfo, return type) tuples.
+code_typed(f, types...) = Any[(ci, rt) for (ci, rt) in Base.code_typed(f, Tuple{types...})]
+
+# The direct subtypes of an abstract type.
+subtypes(t) = Any[s for s in Base.subtypes(t)]
+
+code_llvm(f, types...) = sprint(Base.code_llvm, f, Tuple{types...})
+
+code_native(f, types...) = sprint(Base.code_native, f, Tuple{types...})
+
+# Evaluates code read from io in Main, one complete top-level expression at a
+# time, and returns the value of the last one. Like include, errors are thrown
+# as a LoadError with the line of the failing expression.
+function load(io::IO, name::AbstractString)
+    name = String(name)
+    file = Symbol(name)
+    ret = nothing
+    # bytes read but not evaluated yet, starting at line.
+    buf = UInt8[]
+    line = 1
+    while true
+        done = eof(io)
+        if !done
+            # reading at least as much as is pending keeps loading linear,
+            # however long an incomplete expression gets.
+            append!(buf, read(io, max(length(buf), 4096)))
+            done = eof(io)
+        end
+
+        # an expression is only complete once the line it ends on is.
+        n = done ? length(buf) : findlast(buf, UInt8('\n'))
+        code = String(buf[1:n])
+        pos = 1
+        while true
+            start = skip_blank(buf, pos, n)
+            exline = line + count_lines(buf, pos, start)
+            # line numbers in ex count from pos.
+            ex, next = parse(code, pos, raise=false)
+            if isa(ex, Expr) && ex.head === :error && ex.args[1] == "end of input"
+                line += count_lines(buf, pos, n + 1)
+                pos = n + 1
+                break
+            elseif isa(ex, Expr) && ex.head === :incomplete && !done
+                break
+            elseif isa(ex, Expr) && (ex.head === :error || ex.head === :incomplete)
+                throw(LoadError(name, exline, ParseError(string(ex.args[1]))))
+            end
+
+            try
+                ret = eval(Main, relocate(ex, line - 1, file))
+            catch err
+                rethrow(LoadError(name, exline, err))
+            end
+            line += count_lines(buf, pos, next)
+            pos = next
+        end
+
+        deleteat!(buf, 1:pos-1)
+        done && return ret
+    end
+end
+
+# Skips whitespace and line comments in buf[pos:n].
+function skip_blank(buf, pos, n)
+    while pos <= n
+        c = buf[pos]
+        if c == UInt8('#') && !(pos < n && buf[pos+1] == UInt8('='))
+            while pos <= n && buf[pos] != UInt8('\n')
+                pos += 1
+            end
+        elseif c in b" \t\r\n"
+            pos += 1
+        else
+            break
+        end
+    end
+    pos
+end
+
+# The number of newlines in buf[from:to-1].
+count_lines(buf, from, to) = count(c -> c == UInt8('\n'), view(buf, from:to-1))
+
+# Moves the line numbers in ex down by offset and sets their file.
+function relocate(ex, offset, file)
+    if isa(ex, LineNumberNode)
+        LineNumberNode(ex.line + offset)
+    elseif isa(ex, Expr) && ex.head === :line
+        Expr(:line, ex.args[1] + offset, file)
+    elseif isa(ex, Expr)
+        Expr(ex.head, map(arg -> relocate(arg, offset, file), ex.args)...)
+    else
+        ex
+    end
+end
+
+end
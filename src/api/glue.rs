@@ -0,0 +1,30 @@
+//! Module providing access to the Julia side of julia-rs, a module with
+//! helper types and functions which are easier to write in Julia itself.
+//!
+//! The module is defined as `Main.JuliaRs` the first time it's needed.
+
+use sys::*;
+use error::Result;
+use string::IntoCString;
+use super::{Value, JlValue, Module, Function};
+
+/// Julia source of the JuliaRs module.
+const GLUE_JL: &str = include_str!("glue.jl");
+
+/// Returns the JuliaRs module, defining it first if necessary.
+pub(crate) fn module() -> Result<Module> {
+    let main = unsafe { Module::new_unchecked(jl_main_module) };
+    if let Ok(glue) = main.global("JuliaRs") {
+        return Module::from_value(glue);
+    }
+
+    let source = GLUE_JL.into_cstring();
+    let glue = unsafe { jl_eval_string(source.as_ptr()) };
+    jl_catch!();
+    Value::new(glue).and_then(Module::from_value)
+}
+
+/// Looks up a function in the JuliaRs module.
+pub(crate) fn function(name: &str) -> Result<Function> {
+    module()?.function(name)
+}
@@ -0,0 +1,206 @@
+//! Module providing a way to interrupt running Julia code from other threads.
+//!
+//! Interrupts are delivered the same way Ctrl-C is in the Julia REPL: Julia's
+//! signal handler notices the SIGINT and throws an `InterruptException` in
+//! the thread that initialized the runtime once it reaches a safepoint. Code
+//! stuck in a C call or a tight loop without allocations might not notice it
+//! until it returns to Julia.
+//!
+//! Julia has no entry point for interrupting it other than SIGINT, so using
+//! interrupts changes process-wide signal state:
+//!
+//!  - `Julia::interrupt_handle` turns off exit-on-SIGINT, so SIGINT from any
+//!    source, e.g. Ctrl-C in the terminal, throws an `InterruptException`
+//!    instead of exiting the process.
+//!  - The signal is sent to the whole process. Julia blocks SIGINT in the
+//!    threads it creates and waits for it in a listener thread. Host threads
+//!    started before Julia was initialized don't inherit that mask and must
+//!    block SIGINT themselves, otherwise the signal may be delivered to them
+//!    instead and take its default action, killing the process.
+
+use std::io;
+use std::sync::{Arc, Mutex, Condvar};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use sys::*;
+use error::{Result, Error};
+use string::IntoCString;
+use super::{Julia, Value, Exception};
+use super::base_function;
+
+/// A Send and Sync handle used to interrupt the Julia runtime from any
+/// thread.
+#[derive(Clone, Debug)]
+pub struct InterruptHandle {
+    _private: (),
+}
+
+impl InterruptHandle {
+    /// Requests an `InterruptException` to be thrown at the next safepoint.
+    ///
+    /// Sends SIGINT to the process, see the module documentation for which
+    /// threads must block it.
+    ///
+    /// ## Errors
+    ///
+    /// Returns Error::IOError if the signal couldn't be raised.
+    #[cfg(unix)]
+    pub fn interrupt(&self) -> Result<()> {
+        // SIGINT must be sent to the process, not the Julia thread, because
+        // Julia blocks it in every thread but its signal listener, which is
+        // where it turns the signal into an InterruptException.
+        let ret = unsafe { ::libc::kill(::libc::getpid(), ::libc::SIGINT) };
+        if ret != 0 {
+            return Err(From::from(io::Error::last_os_error()));
+        }
+        Ok(())
+    }
+
+    /// Requests an `InterruptException` to be thrown at the next safepoint.
+    ///
+    /// ## Errors
+    ///
+    /// Always returns Error::IOError, interrupting is only supported on unix.
+    #[cfg(not(unix))]
+    pub fn interrupt(&self) -> Result<()> {
+        Err(From::from(io::Error::new(
+            io::ErrorKind::Other,
+            "interrupting Julia is not supported on this platform",
+        )))
+    }
+}
+
+/// How often the watchdog of `eval_with_timeout` repeats the interrupt after
+/// the deadline. Julia forces an `InterruptException` into code that doesn't
+/// reach a safepoint, e.g. `while true end`, once it gets several SIGINTs in
+/// quick succession, like pressing Ctrl-C repeatedly in the REPL.
+const REPEAT_INTERVAL_MS: u64 = 100;
+
+/// How long to wait for interrupts still in flight after an interrupted
+/// evaluation stopped, so that they aren't thrown by the next Julia code.
+const DRAIN_SECS: f64 = 0.05;
+
+/// State of the watchdog of `eval_with_timeout`.
+#[derive(Clone, Copy, PartialEq, Eq)]
+struct Watch {
+    finished: bool,
+    interrupted: bool,
+}
+
+impl Julia {
+    /// Returns a handle which can be sent to other threads to interrupt
+    /// Julia code running in this one.
+    ///
+    /// Calls `set_exit_on_sigint(false)`, which affects the whole process and
+    /// isn't undone when the handle is dropped, since an interrupt would
+    /// otherwise exit the process.
+    pub fn interrupt_handle(&self) -> InterruptHandle {
+        self.set_exit_on_sigint(false);
+        InterruptHandle { _private: () }
+    }
+
+    /// Chooses whether SIGINT exits the process, which is the default for an
+    /// embedded runtime, or throws an `InterruptException` in the Julia
+    /// thread, like in the Julia REPL.
+    ///
+    /// This is process-wide state and applies to every source of SIGINT, e.g.
+    /// Ctrl-C in the terminal.
+    pub fn set_exit_on_sigint(&self, exit: bool) {
+        unsafe {
+            jl_exit_on_sigint(exit as i32);
+            if !exit {
+                jl_install_sigint_handler();
+            }
+        }
+    }
+
+    /// Parses and evaluates string, interrupting it if it doesn't finish in
+    /// time.
+    ///
+    /// The interrupt is repeated until the evaluation stops, so that Julia
+    /// eventually forces it into code that doesn't reach a safepoint, e.g. a
+    /// loop without allocations. Code blocked in a C call can't be
+    /// interrupted until it returns to Julia.
+    ///
+    /// After an interrupted evaluation, interrupts still in flight are waited
+    /// for briefly. If the evaluation finishes right as it's being
+    /// interrupted, the InterruptException may still be thrown by the next
+    /// Julia code instead.
+    ///
+    /// ## Errors
+    ///
+    /// Returns Error::Timeout if the evaluation was interrupted.
+    pub fn eval_with_timeout<S: IntoCString>(&mut self, string: S, timeout: Duration) -> Result<Value> {
+        let handle = self.interrupt_handle();
+        with_timeout(&handle, timeout, || self.eval_string(string))
+    }
+}
+
+/// Runs a closure calling into Julia, interrupting it if it doesn't finish in
+/// time, see `Julia::eval_with_timeout`.
+pub(crate) fn with_timeout<T, F>(handle: &InterruptHandle, timeout: Duration, f: F) -> Result<T>
+where
+    F: FnOnce() -> Result<T>,
+{
+    let state = Arc::new((
+        Mutex::new(Watch {
+            finished: false,
+            interrupted: false,
+        }),
+        Condvar::new(),
+    ));
+
+    let watchdog = {
+        let handle = handle.clone();
+        let state = state.clone();
+        thread::spawn(move || {
+            let (ref lock, ref cvar) = *state;
+            let mut deadline = Instant::now() + timeout;
+            let mut watch = match lock.lock() {
+                Ok(watch) => watch,
+                Err(_) => return,
+            };
+
+            while !watch.finished {
+                let now = Instant::now();
+                if now >= deadline {
+                    if handle.interrupt().is_err() {
+                        return;
+                    }
+                    watch.interrupted = true;
+                    deadline = now + Duration::from_millis(REPEAT_INTERVAL_MS);
+                    continue;
+                }
+
+                watch = match cvar.wait_timeout(watch, deadline - now) {
+                    Ok((watch, _)) => watch,
+                    Err(_) => return,
+                };
+            }
+        })
+    };
+
+    let ret = f();
+
+    let interrupted = {
+        let (ref lock, ref cvar) = *state;
+        let mut watch = lock.lock()?;
+        watch.finished = true;
+        cvar.notify_one();
+        watch.interrupted
+    };
+    let _ = watchdog.join();
+
+    if interrupted {
+        // any exception is an interrupt which arrived late, and ignored.
+        if let Ok(sleep) = base_function("sleep") {
+            let _ = sleep.call1(&Value::from(DRAIN_SECS));
+        }
+    }
+
+    match ret {
+        Err(Error::UnhandledException(Exception::Interrupt(_))) if interrupted => Err(Error::Timeout),
+        ret => ret,
+    }
+}
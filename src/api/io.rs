@@ -0,0 +1,240 @@
+//! Module providing Julia IO objects backed by Rust readers and writers.
+//!
+//! A JlIO is a `JuliaRs.RustIO <: IO`, whose methods call back into the
+//! Rust stream it was created with. It can be passed to any Julia function
+//! expecting an IO, e.g. `write(io, data)` or `readline(io)`.
+
+use std::io::{self, Read, Write};
+use std::panic::{self, AssertUnwindSafe};
+use std::mem;
+use std::slice;
+
+use libc::{c_int, c_void};
+
+use error::{Result, Error};
+use super::Value;
+use super::glue;
+use super::base_function;
+
+/// The Rust side of a RustIO.
+struct Stream {
+    reader: Option<Box<dyn Read>>,
+    writer: Option<Box<dyn Write>>,
+    // a byte read ahead by eof.
+    peeked: Option<u8>,
+}
+
+impl Stream {
+    /// Reads as many bytes as possible, until `buf` is full or the end of the
+    /// stream is reached.
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let mut n = 0;
+        if let Some(x) = self.peeked.take() {
+            if buf.is_empty() {
+                self.peeked = Some(x);
+                return Ok(0);
+            }
+            buf[0] = x;
+            n = 1;
+        }
+
+        let reader = match self.reader {
+            Some(ref mut reader) => reader,
+            None => return Ok(n),
+        };
+        while n < buf.len() {
+            match reader.read(&mut buf[n..]) {
+                Ok(0) => break,
+                Ok(m) => n += m,
+                Err(ref err) if err.kind() == io::ErrorKind::Interrupted => (),
+                Err(err) => return Err(err),
+            }
+        }
+        Ok(n)
+    }
+
+    fn eof(&mut self) -> io::Result<bool> {
+        if self.peeked.is_some() {
+            return Ok(false);
+        }
+
+        let mut x = [0];
+        let n = self.read(&mut x)?;
+        if n == 0 {
+            Ok(true)
+        } else {
+            self.peeked = Some(x[0]);
+            Ok(false)
+        }
+    }
+}
+
+/// Runs a callback, turning errors and panics into -1, since neither may
+/// cross into Julia.
+fn guard<T, F>(f: F) -> T
+where
+    T: From<i8>,
+    F: FnOnce() -> io::Result<T>,
+{
+    match panic::catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(x)) => x,
+        _ => T::from(-1),
+    }
+}
+
+extern "C" fn stream_read(handle: *mut c_void, buf: *mut u8, len: usize) -> isize {
+    guard(|| {
+        let stream = unsafe { &mut *(handle as *mut Stream) };
+        let buf = unsafe { slice::from_raw_parts_mut(buf, len) };
+        stream.read(buf).map(|n| n as isize)
+    })
+}
+
+extern "C" fn stream_write(handle: *mut c_void, buf: *const u8, len: usize) -> isize {
+    guard(|| {
+        let stream = unsafe { &mut *(handle as *mut Stream) };
+        let buf = unsafe { slice::from_raw_parts(buf, len) };
+        match stream.writer {
+            Some(ref mut writer) => writer.write_all(buf).map(|_| len as isize),
+            None => Err(io::Error::new(io::ErrorKind::BrokenPipe, "not writable")),
+        }
+    })
+}
+
+extern "C" fn stream_flush(handle: *mut c_void) -> c_int {
+    guard(|| {
+        let stream = unsafe { &mut *(handle as *mut Stream) };
+        match stream.writer {
+            Some(ref mut writer) => writer.flush().map(|_| 0),
+            None => Ok(0),
+        }
+    })
+}
+
+extern "C" fn stream_eof(handle: *mut c_void) -> c_int {
+    guard(|| {
+        let stream = unsafe { &mut *(handle as *mut Stream) };
+        stream.eof().map(|eof| eof as c_int)
+    })
+}
+
+extern "C" fn stream_close(handle: *mut c_void) -> c_int {
+    guard(|| {
+        let stream = unsafe { &mut *(handle as *mut Stream) };
+        stream.reader = None;
+        stream.peeked = None;
+        match stream.writer.take() {
+            Some(mut writer) => writer.flush().map(|_| 0),
+            None => Ok(0),
+        }
+    })
+}
+
+extern "C" fn stream_free(handle: *mut c_void) {
+    let _ = panic::catch_unwind(|| unsafe {
+        drop(Box::from_raw(handle as *mut Stream));
+    });
+}
+
+/// A Julia IO reading from a Rust Read and/or writing to a Rust Write.
+///
+/// The Rust stream is dropped when the IO is closed or garbage collected.
+pub struct JlIO {
+    inner: Value,
+}
+
+impl JlIO {
+    fn with_stream(stream: Stream) -> Result<JlIO> {
+        let readable = stream.reader.is_some();
+        let writable = stream.writer.is_some();
+        let handle = Box::into_raw(Box::new(stream));
+
+        let args = [
+            Value::from(handle as usize),
+            Value::from(stream_read as usize),
+            Value::from(stream_write as usize),
+            Value::from(stream_flush as usize),
+            Value::from(stream_eof as usize),
+            Value::from(stream_close as usize),
+            Value::from(stream_free as usize),
+            Value::from(readable),
+            Value::from(writable),
+        ];
+
+        let rustio = match glue::function("RustIO") {
+            Ok(rustio) => rustio,
+            Err(err) => {
+                stream_free(handle as *mut c_void);
+                return Err(err);
+            }
+        };
+        // once constructed, the RustIO's finalizer frees the stream. It's
+        // attached last, so it isn't if the constructor throws.
+        match rustio.call(&args) {
+            Ok(inner) => Ok(JlIO { inner: inner }),
+            Err(Error::UnhandledException(ex)) => {
+                stream_free(handle as *mut c_void);
+                Err(Error::UnhandledException(ex))
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Creates a readable IO.
+    pub fn from_reader<R: Read + 'static>(reader: R) -> Result<JlIO> {
+        JlIO::with_stream(Stream {
+            reader: Some(Box::new(reader)),
+            writer: None,
+            peeked: None,
+        })
+    }
+
+    /// Creates a writable IO.
+    pub fn from_writer<W: Write + 'static>(writer: W) -> Result<JlIO> {
+        JlIO::with_stream(Stream {
+            reader: None,
+            writer: Some(Box::new(writer)),
+            peeked: None,
+        })
+    }
+
+    /// Creates an IO which is both readable and writable, e.g. for a socket.
+    pub fn new<R, W>(reader: R, writer: W) -> Result<JlIO>
+    where
+        R: Read + 'static,
+        W: Write + 'static,
+    {
+        JlIO::with_stream(Stream {
+            reader: Some(Box::new(reader)),
+            writer: Some(Box::new(writer)),
+            peeked: None,
+        })
+    }
+
+    /// Creates a readable IO borrowing a reader.
+    ///
+    /// This function is unsafe, because the IO must be closed before the
+    /// reader goes out of scope.
+    pub(crate) unsafe fn from_borrowed_reader<R: Read>(reader: &mut R) -> Result<JlIO> {
+        let reader: &mut dyn Read = reader;
+        let reader: &'static mut dyn Read = mem::transmute(reader);
+        JlIO::from_reader(reader)
+    }
+
+    /// Immutably borrows the inner Value.
+    pub fn inner_ref(&self) -> &Value {
+        &self.inner
+    }
+
+    /// Consumes self and returns the inner Value.
+    pub fn into_inner(self) -> Value {
+        self.inner
+    }
+
+    /// Closes the IO, flushing and dropping the Rust stream.
+    pub fn close(&self) -> Result<()> {
+        let close = base_function("close")?;
+        close.call1(&self.inner)?;
+        Ok(())
+    }
+}
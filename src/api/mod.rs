@@ -35,22 +35,62 @@ pub mod value;
 #[macro_use]
 pub mod array;
 pub mod function;
+pub mod expr;
+pub mod code;
+pub mod parse;
+#[macro_use]
+pub mod quasi;
 pub mod sym;
 pub mod module;
 pub mod datatype;
 pub mod task;
 pub mod exception;
 pub mod primitive;
+pub mod executor;
+pub mod thread;
+pub mod channel;
+pub mod event;
+pub mod interrupt;
+pub mod sysimg;
+pub mod system;
+pub mod output;
+pub mod io;
+pub mod gc;
+pub mod package;
+pub mod sandbox;
+pub mod reload;
+mod glue;
 
 pub use self::value::{Value, JlValue};
 pub use self::array::{Array, Svec};
 pub use self::function::Function;
+pub use self::expr::{Expr, ExprVisitor};
+pub use self::code::CodeInfo;
+pub use self::parse::{Parsed, SyntaxError, Position};
 pub use self::sym::{Symbol, IntoSymbol};
 pub use self::module::Module;
-pub use self::datatype::Datatype;
-pub use self::task::Task;
-pub use self::exception::Exception;
+pub use self::datatype::{Datatype, JlType};
+pub use self::task::{Task, TaskState};
+pub use self::exception::{Exception, LoadError};
 pub use self::primitive::*;
+pub use self::executor::{JuliaExecutor, JuliaFuture, ChannelStream};
+pub use self::thread::GcSafe;
+pub use self::channel::JlChannel;
+pub use self::interrupt::InterruptHandle;
+pub use self::sysimg::ImageBuilder;
+pub use self::system::{SystemInfo, Stat};
+pub use self::io::JlIO;
+pub use self::sandbox::Sandbox;
+pub use self::reload::{Reloader, Reload};
+pub use self::gc::{GcStats, GcMonitor, GcDisabledGuard, WeakValue};
+
+/// Looks up a function in Base without needing a Julia handle.
+pub(crate) fn base_function(name: &str) -> Result<Function> {
+    let name = name.into_cstring();
+    let raw = unsafe { jl_get_function(jl_base_module, name.as_ptr()) };
+    jl_catch!();
+    Function::new(raw)
+}
 
 /// Blank struct for controlling the Julia garbage collector.
 pub struct Gc;
@@ -228,22 +268,30 @@ impl Julia {
         &self.top
     }
 
-    /// Loads a Julia script from any Read without evaluating it.
+    /// Loads and evaluates a Julia script from any Read.
+    ///
+    /// The script is read and evaluated one complete top-level expression at
+    /// a time, so it doesn't have to fit in memory at once. Returns the value
+    /// of the last expression.
+    ///
+    /// Like `include`, errors are thrown as a LoadError with `name` and the
+    /// line of the failing expression, see `Exception::load_error`.
     pub fn load<R: Read, S: IntoCString>(&mut self, r: &mut R, name: Option<S>) -> Result<Value> {
-        let mut content = String::new();
-        let len = r.read_to_string(&mut content)?;
-        let content = content.into_cstring();
-        let content = content.as_ptr();
-
-        let name = name.map(|s| s.into_cstring()).unwrap_or_else(
-            || "string".into_cstring(),
+        let name = name.map(Value::from).unwrap_or_else(
+            || Value::from("string"),
         );
-        let name = name.as_ptr();
 
-        //let raw = unsafe { jl_load_file_string(content, len, ptr::null::<i8>() as *mut _) };
-        let raw = unsafe { jl_load_file_string(content, len, name as *mut _) };
-        jl_catch!();
-        Value::new(raw)
+        let load = glue::function("load")?;
+        // the IO is closed before r goes out of scope.
+        let io = unsafe { JlIO::from_borrowed_reader(r)? };
+        let ret = load.call2(io.inner_ref(), &name);
+        let closed = io.close();
+        let flushed = self.flush_output();
+
+        let ret = ret?;
+        closed?;
+        flushed?;
+        Ok(ret)
     }
 
     /// Parses and evaluates string.
@@ -252,13 +300,13 @@ impl Julia {
         let string = string.as_ptr();
 
         let ret = unsafe { jl_eval_string(string) };
-        jl_catch!();
-        Value::new(ret).map_err(|_| Error::EvalError)
+        output::finish(Exception::catch(), ret, Error::EvalError)
     }
 }
 
 impl Drop for Julia {
     fn drop(&mut self) {
+        let _ = self.flush_output();
         self.at_exit.map(|s| unsafe { jl_atexit_hook(s) });
     }
 }
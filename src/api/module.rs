@@ -1,22 +1,221 @@
 
 //! Module providing a wrapper for the native Julia module object.
 
+use std::convert::TryFrom;
+use std::env;
+use std::path::Path;
+use std::vec;
+
 use sys::*;
-use error::Result;
-use super::{Value, JlValue, Function, IntoSymbol};
+use error::{Result, Error};
+use string::IntoCString;
+use super::{Value, JlValue, Function, Symbol, IntoSymbol, Array, Expr, Exception};
+use super::base_function;
+use super::glue;
+use super::output;
 
 jlvalues! {
     pub struct Module(jl_module_t);
 }
 
 impl Module {
+    /// Creates a new module with a name, which uses Core, but not Base.
+    ///
+    /// If `parent` is given, the new module is bound as a constant in it,
+    /// like a submodule defined in Julia would be. Otherwise, its parent is
+    /// Main, but it's not bound anywhere, so it must be kept reachable from
+    /// Julia, e.g. bound to a global, for as long as it's used. Rust handles
+    /// don't keep it from being collected.
+    pub fn new<S: IntoSymbol>(name: S, parent: Option<&Module>) -> Result<Module> {
+        let name = name.into_symbol()?;
+        let raw = unsafe { jl_new_module(name.lock()?) };
+        jl_catch!();
+        let module: Module = JlValue::new(raw)?;
+
+        match parent {
+            Some(parent) => parent.add_submodule(&module)?,
+            None => unsafe {
+                (*raw).parent = jl_main_module;
+                jl_gc_wb(raw, jl_main_module);
+            },
+        }
+        Ok(module)
+    }
+
+    /// Makes `child` a submodule of this module, binding it as a constant
+    /// under its own name.
+    pub fn add_submodule(&self, child: &Module) -> Result<()> {
+        let parent = self.lock()?;
+        let raw = child.lock()?;
+        let name = unsafe {
+            (*raw).parent = parent;
+            jl_gc_wb(raw, parent);
+            (*raw).name
+        };
+
+        unsafe {
+            jl_set_const(parent, name, raw as *mut jl_value_t);
+        }
+        jl_catch!();
+        Ok(())
+    }
+
+    /// Checks if this module is `parent` or nested inside of it.
+    pub fn is_submodule_of(&self, parent: &Module) -> Result<bool> {
+        let parent = parent.lock()?;
+        let mut module = self.lock()?;
+        loop {
+            if module == parent {
+                return Ok(true);
+            }
+
+            let next = unsafe { (*module).parent };
+            if next.is_null() || next == module {
+                return Ok(false);
+            }
+            module = next;
+        }
+    }
+
+    /// Adds `using Base` and the other standard imports of modules defined in
+    /// Julia.
+    pub fn add_standard_imports(&self) -> Result<()> {
+        unsafe {
+            jl_add_standard_imports(self.lock()?);
+        }
+        jl_catch!();
+        Ok(())
+    }
+
+    /// Makes all names exported by `from` available in this module, like
+    /// `using From`.
+    pub fn using(&self, from: &Module) -> Result<()> {
+        unsafe {
+            jl_module_using(self.lock()?, from.lock()?);
+        }
+        jl_catch!();
+        Ok(())
+    }
+
+    /// Makes a single name from `from` available in this module, like
+    /// `using From.sym`.
+    pub fn using_name<S: IntoSymbol>(&self, from: &Module, sym: S) -> Result<()> {
+        let sym = sym.into_symbol()?;
+        unsafe {
+            jl_module_use(self.lock()?, from.lock()?, sym.lock()?);
+        }
+        jl_catch!();
+        Ok(())
+    }
+
+    /// Imports a name from `from`, so that it can be extended with new methods,
+    /// like `import From.sym`.
+    pub fn import<S: IntoSymbol>(&self, from: &Module, sym: S) -> Result<()> {
+        let sym = sym.into_symbol()?;
+        unsafe {
+            jl_module_import(self.lock()?, from.lock()?, sym.lock()?);
+        }
+        jl_catch!();
+        Ok(())
+    }
+
+    /// Imports all names exported by `from`, like `importall From`.
+    pub fn import_all(&self, from: &Module) -> Result<()> {
+        unsafe {
+            jl_module_importall(self.lock()?, from.lock()?);
+        }
+        jl_catch!();
+        Ok(())
+    }
+
+    /// Exports a name from this module.
+    pub fn export<S: IntoSymbol>(&self, sym: S) -> Result<()> {
+        let sym = sym.into_symbol()?;
+        unsafe {
+            jl_module_export(self.lock()?, sym.lock()?);
+        }
+        jl_catch!();
+        Ok(())
+    }
+
+    /// Returns the name of the module.
+    pub fn name(&self) -> Result<Symbol> {
+        let raw = self.lock()?;
+        let name = unsafe { (*raw).name };
+        Symbol::new(name)
+    }
+
+    /// Returns the module this one is defined in. The parent of Main is Main
+    /// itself.
+    pub fn parent(&self) -> Result<Module> {
+        let raw = self.lock()?;
+        let parent = unsafe { (*raw).parent };
+        JlValue::new(parent)
+    }
+
+    /// Returns the names in the module, like Julia's `names`.
+    ///
+    /// Only exported names are returned, unless `all` is true. Names imported
+    /// from other modules are only returned if `imported` is true.
+    pub fn names(&self, all: bool, imported: bool) -> Result<Vec<Symbol>> {
+        let names = base_function("names")?;
+        let module = Value::new(self.lock()? as *mut jl_value_t)?;
+        let names = names.call3(&module, &Value::from(all), &Value::from(imported))?;
+        let names = Array::from_value(names)?;
+
+        let mut symbols = vec![];
+        for name in names.as_vec()? {
+            symbols.push(Symbol::from_value(name)?);
+        }
+        Ok(symbols)
+    }
+
+    /// Returns an iterator over all names defined in the module and the
+    /// values bound to them.
+    pub fn bindings(&self) -> Result<Bindings> {
+        Ok(Bindings {
+            module: self.clone(),
+            names: self.names(true, false)?.into_iter(),
+        })
+    }
+
+    /// Checks if a global is defined in the module, or imported into it.
+    pub fn is_defined<S: IntoSymbol>(&self, sym: S) -> Result<bool> {
+        let sym = sym.into_symbol()?;
+        let p = unsafe { jl_boundp(self.lock()?, sym.lock()?) };
+        jl_catch!();
+        Ok(p != 0)
+    }
+
+    /// Checks if a global is a constant.
+    pub fn is_const<S: IntoSymbol>(&self, sym: S) -> Result<bool> {
+        let sym = sym.into_symbol()?;
+        let p = unsafe { jl_is_const(self.lock()?, sym.lock()?) };
+        jl_catch!();
+        Ok(p != 0)
+    }
+
+    /// Checks if a name was explicitly imported from another module.
+    pub fn is_imported<S: IntoSymbol>(&self, sym: S) -> Result<bool> {
+        let sym = sym.into_symbol()?;
+        let p = unsafe { jl_is_imported(self.lock()?, sym.lock()?) };
+        jl_catch!();
+        Ok(p != 0)
+    }
+
     /// Returns a global bound to the symbol `sym`.
+    ///
+    /// ## Errors
+    ///
+    /// Returns Error::UndefVar if nothing is bound to `sym`.
     pub fn global<S: IntoSymbol>(&self, sym: S) -> Result<Value> {
         let module = self.lock()?;
         let sym = sym.into_symbol()?;
-        let sym = sym.into_inner()?;
-        let raw = unsafe { jl_get_global(module, sym) };
+        let raw = unsafe { jl_get_global(module, sym.lock()?) };
         jl_catch!();
+        if raw.is_null() {
+            return Err(Error::UndefVar(String::try_from(&sym)?));
+        }
         Value::new(raw)
     }
 
@@ -52,4 +251,74 @@ impl Module {
         jl_catch!();
         Ok(())
     }
+
+    /// Evaluates an expression in this module, as if it appeared at the
+    /// top level of it.
+    pub fn eval(&self, expr: &Expr) -> Result<Value> {
+        let module = self.lock()?;
+        let expr = expr.lock()?;
+        let ret = unsafe { jl_toplevel_eval_in(module, expr as *mut _) };
+        output::finish(Exception::catch(), ret, Error::EvalError)
+    }
+
+    /// Parses and evaluates string in this module, like
+    /// `Julia::eval_string` does in Main.
+    pub fn eval_string(&self, code: &str) -> Result<Value> {
+        let module = self.lock()?;
+        let expr = parse_input(code, "string")?;
+        let expr = expr.lock()?;
+        let ret = unsafe { jl_toplevel_eval_in(module, expr) };
+        output::finish(Exception::catch(), ret, Error::EvalError)
+    }
+
+    /// Evaluates a file in this module, like `include` inside of it.
+    ///
+    /// `@__FILE__` and `@__DIR__` refer to the file and includes relative to
+    /// it are resolved against its directory, rather than the working
+    /// directory of the process. Returns the value of the last expression.
+    pub fn include<P: AsRef<Path>>(&self, path: P) -> Result<Value> {
+        let path = path.as_ref();
+        let path = if path.is_absolute() {
+            path.to_owned()
+        } else {
+            env::current_dir()?.join(path)
+        };
+
+        let include = glue::function("include")?;
+        let module: Value = self.to_value()?;
+        include.call2(&module, &Value::from(&*path.to_string_lossy()))
+    }
+}
+
+/// Parses all of string, like the REPL parses its input, without evaluating
+/// it. Syntax errors are returned as `:error` or `:incomplete` expressions.
+pub(crate) fn parse_input(code: &str, filename: &str) -> Result<Value> {
+    let len = code.len();
+    let code = code.into_cstring();
+    let filename_len = filename.len();
+    let filename = filename.into_cstring();
+
+    let raw = unsafe { jl_parse_input_line(code.as_ptr(), len, filename.as_ptr(), filename_len) };
+    jl_catch!();
+    Value::new(raw)
+}
+
+/// An iterator over the globals of a module and their values, skipping the
+/// ones which aren't assigned yet.
+pub struct Bindings {
+    module: Module,
+    names: vec::IntoIter<Symbol>,
+}
+
+impl Iterator for Bindings {
+    type Item = (Symbol, Value);
+
+    fn next(&mut self) -> Option<(Symbol, Value)> {
+        for name in &mut self.names {
+            if let Ok(value) = self.module.global(name.clone()) {
+                return Some((name, value));
+            }
+        }
+        None
+    }
 }
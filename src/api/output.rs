@@ -0,0 +1,223 @@
+//! Module providing redirection and capturing of Julia's standard output and
+//! error streams.
+//!
+//! Redirection works by replacing `Base.STDOUT` and `Base.STDERR` with an
+//! in-memory buffer, so it applies to everything written through them, but
+//! not to output written directly to the file descriptors, e.g. by C
+//! libraries.
+//!
+//! Redirected output is flushed after every evaluation and function call, so
+//! it's written before the call returns.
+
+use std::cell::RefCell;
+use std::io::Write;
+
+use sys::*;
+use error::{Result, Error};
+use super::{Julia, Value, JlValue, Exception};
+use super::array::ByteArray;
+use super::base_function;
+
+/// One of the two standard output streams.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Stream {
+    Stdout,
+    Stderr,
+}
+
+impl Stream {
+    /// Returns the name of the global in Base holding the stream.
+    fn global(self) -> &'static str {
+        match self {
+            Stream::Stdout => "STDOUT",
+            Stream::Stderr => "STDERR",
+        }
+    }
+}
+
+/// A stream replaced by a buffer which is drained into a Rust writer.
+struct Redirect {
+    writer: Box<dyn Write>,
+    buffer: Value,
+    original: Value,
+}
+
+/// Redirections of the standard output streams of the runtime.
+#[derive(Default)]
+struct Output {
+    stdout: Option<Redirect>,
+    stderr: Option<Redirect>,
+}
+
+thread_local! {
+    // Julia runs on a single thread, so this is the runtime's output.
+    static OUTPUT: RefCell<Output> = RefCell::new(Output::default());
+}
+
+/// Writes pending output of redirected streams into their writers and
+/// flushes them.
+pub(crate) fn flush() -> Result<()> {
+    OUTPUT.with(|output| {
+        // taking the output calls into Julia, which flushes again.
+        let mut output = match output.try_borrow_mut() {
+            Ok(output) => output,
+            Err(_) => return Ok(()),
+        };
+        let output = &mut *output;
+        for redirect in output.stdout.iter_mut().chain(output.stderr.iter_mut()) {
+            let bytes = take_buffer(&redirect.buffer)?;
+            redirect.writer.write_all(&bytes)?;
+            redirect.writer.flush()?;
+        }
+        Ok(())
+    })
+}
+
+/// Flushes the output of an evaluation or call, which returned `ret` or threw
+/// `ex`, and returns its result. The exception takes precedence over errors
+/// from flushing, and `null` is returned if `ret` is null.
+pub(crate) fn finish(ex: Option<Exception>, ret: *mut jl_value_t, null: Error) -> Result<Value> {
+    let flushed = flush();
+    if let Some(ex) = ex {
+        return Err(Error::UnhandledException(ex));
+    }
+    flushed?;
+    Value::new(ret).map_err(|_| null)
+}
+
+/// Creates an empty IOBuffer.
+fn new_buffer() -> Result<Value> {
+    let iobuffer = base_function("IOBuffer")?;
+    iobuffer.call0()
+}
+
+/// Takes everything written to an IOBuffer, leaving it empty.
+fn take_buffer(buffer: &Value) -> Result<Vec<u8>> {
+    let take = base_function("take!")?;
+    let bytes = take.call1(buffer)?;
+    ByteArray::from_value(bytes)?.as_vec()
+}
+
+impl Julia {
+    /// Replaces a stream with a value and returns the previous one.
+    fn swap_stream(&mut self, stream: Stream, value: &Value) -> Result<Value> {
+        let name = stream.global();
+        let original = self.base().global(name)?;
+        self.base().set(name, value)?;
+        Ok(original)
+    }
+
+    fn redirect(&mut self, stream: Stream, writer: Box<dyn Write>) -> Result<()> {
+        self.restore(stream)?;
+
+        let buffer = new_buffer()?;
+        let original = self.swap_stream(stream, &buffer)?;
+        let redirect = Redirect {
+            writer: writer,
+            buffer: buffer,
+            original: original,
+        };
+
+        OUTPUT.with(|output| {
+            let mut output = output.borrow_mut();
+            match stream {
+                Stream::Stdout => output.stdout = Some(redirect),
+                Stream::Stderr => output.stderr = Some(redirect),
+            }
+        });
+        Ok(())
+    }
+
+    fn restore(&mut self, stream: Stream) -> Result<()> {
+        self.flush_output()?;
+
+        let redirect = OUTPUT.with(|output| {
+            let mut output = output.borrow_mut();
+            match stream {
+                Stream::Stdout => output.stdout.take(),
+                Stream::Stderr => output.stderr.take(),
+            }
+        });
+        if let Some(redirect) = redirect {
+            self.swap_stream(stream, &redirect.original)?;
+        }
+        Ok(())
+    }
+
+    /// Redirects everything Julia writes to its standard output into a Rust
+    /// writer.
+    ///
+    /// Output is written to `writer` and flushed at the end of every
+    /// evaluation and call and whenever `flush_output` is called.
+    pub fn redirect_stdout<W: Write + 'static>(&mut self, writer: W) -> Result<()> {
+        self.redirect(Stream::Stdout, Box::new(writer))
+    }
+
+    /// Redirects everything Julia writes to its standard error into a Rust
+    /// writer.
+    ///
+    /// Output is written to `writer` and flushed at the end of every
+    /// evaluation and call and whenever `flush_output` is called.
+    pub fn redirect_stderr<W: Write + 'static>(&mut self, writer: W) -> Result<()> {
+        self.redirect(Stream::Stderr, Box::new(writer))
+    }
+
+    /// Flushes any pending output and restores the original standard output.
+    pub fn restore_stdout(&mut self) -> Result<()> {
+        self.restore(Stream::Stdout)
+    }
+
+    /// Flushes any pending output and restores the original standard error.
+    pub fn restore_stderr(&mut self) -> Result<()> {
+        self.restore(Stream::Stderr)
+    }
+
+    /// Writes pending output of redirected streams into their writers and
+    /// flushes them.
+    pub fn flush_output(&mut self) -> Result<()> {
+        flush()
+    }
+
+    /// Runs a closure, capturing everything Julia writes to its standard
+    /// output and error in the meantime.
+    ///
+    /// The original streams, or active redirections, are restored afterwards,
+    /// even if the closure fails.
+    pub fn capture<F, T>(&mut self, f: F) -> (Result<T>, String)
+    where
+        F: FnOnce(&mut Julia) -> Result<T>,
+    {
+        let buffer = match new_buffer() {
+            Ok(buffer) => buffer,
+            Err(err) => return (Err(err), String::new()),
+        };
+        let stdout = match self.swap_stream(Stream::Stdout, &buffer) {
+            Ok(stdout) => stdout,
+            Err(err) => return (Err(err), String::new()),
+        };
+        let stderr = match self.swap_stream(Stream::Stderr, &buffer) {
+            Ok(stderr) => stderr,
+            Err(err) => {
+                let _ = self.swap_stream(Stream::Stdout, &stdout);
+                return (Err(err), String::new());
+            }
+        };
+
+        let ret = f(self);
+
+        let restored = self.swap_stream(Stream::Stdout, &stdout).and(
+            self.swap_stream(Stream::Stderr, &stderr),
+        );
+        let captured = take_buffer(&buffer).map(|bytes| {
+            String::from_utf8_lossy(&bytes).into_owned()
+        });
+
+        match (ret, restored, captured) {
+            (Ok(x), Ok(_), Ok(captured)) => (Ok(x), captured),
+            (Err(err), _, Ok(captured)) => (Err(err), captured),
+            (Err(err), _, Err(_)) => (Err(err), String::new()),
+            (Ok(_), Err(err), captured) => (Err(err), captured.unwrap_or_default()),
+            (Ok(_), Ok(_), Err(err)) => (Err(err), String::new()),
+        }
+    }
+}
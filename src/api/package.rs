@@ -0,0 +1,98 @@
+//! Module providing loading of packages and standard libraries and control
+//! over where Julia looks for them.
+//!
+//! Packages are looked up in the directories of `LOAD_PATH` and then in the
+//! package directory, see `Pkg.dir()`. Neither needs a network connection, so
+//! pointing `LOAD_PATH` at local directories is enough to load local packages
+//! offline.
+
+use std::convert::TryFrom;
+use std::path::Path;
+
+use error::Result;
+use super::{Julia, Value, JlValue, Module, Array, Symbol};
+use super::glue;
+use super::base_function;
+
+impl Julia {
+    /// Loads a package, like `import Name`, and returns its module.
+    ///
+    /// Only top-level packages found in `LOAD_PATH` can be loaded. Julia 0.6
+    /// has no standard library packages and submodules of Base, e.g.
+    /// `Base.Test`, are already loaded and can be looked up with `global`.
+    ///
+    /// ## Errors
+    ///
+    /// Errors while loading the package are returned as
+    /// `Exception::Load`, see `Exception::load_error` and
+    /// `Exception::root_cause` to find out what went wrong.
+    pub fn require(&mut self, name: &str) -> Result<Module> {
+        let require = glue::function("require")?;
+        let name = Value::from_value(Symbol::with_name(name)?)?;
+        let module = require.call1(&name);
+        let flushed = self.flush_output();
+        let module = module?;
+        flushed?;
+        Module::from_value(module)
+    }
+
+    /// Loads a package and makes its exported names available in `into`,
+    /// like `using Name` inside `into`. Returns the package's module.
+    pub fn using_into(&mut self, into: &Module, name: &str) -> Result<Module> {
+        let package = self.require(name)?;
+        into.using(&package)?;
+        Ok(package)
+    }
+
+    /// Returns the contents of one of the path vectors in Base.
+    fn paths(&self, name: &str) -> Result<Vec<String>> {
+        let paths = Array::from_value(self.base().global(name)?)?;
+        paths
+            .as_vec()?
+            .iter()
+            .map(String::try_from)
+            .collect()
+    }
+
+    /// Replaces the contents of one of the path vectors in Base.
+    fn set_paths<I, P>(&mut self, name: &str, paths: I) -> Result<()>
+    where
+        I: IntoIterator<Item = P>,
+        P: AsRef<Path>,
+    {
+        let vector = self.base().global(name)?;
+        let empty = base_function("empty!")?;
+        empty.call1(&vector)?;
+        for path in paths {
+            push_path(&vector, path.as_ref())?;
+        }
+        Ok(())
+    }
+
+    /// Returns the directories in `LOAD_PATH`.
+    pub fn load_path(&self) -> Result<Vec<String>> {
+        self.paths("LOAD_PATH")
+    }
+
+    /// Replaces `LOAD_PATH`.
+    pub fn set_load_path<I, P>(&mut self, paths: I) -> Result<()>
+    where
+        I: IntoIterator<Item = P>,
+        P: AsRef<Path>,
+    {
+        self.set_paths("LOAD_PATH", paths)
+    }
+
+    /// Appends a directory to `LOAD_PATH`.
+    pub fn push_load_path<P: AsRef<Path>>(&mut self, path: P) -> Result<()> {
+        let vector = self.base().global("LOAD_PATH")?;
+        push_path(&vector, path.as_ref())
+    }
+}
+
+/// Appends a path to a vector of paths.
+fn push_path(vector: &Value, path: &Path) -> Result<()> {
+    let push = base_function("push!")?;
+    push.call2(vector, &Value::from(&*path.to_string_lossy()))?;
+    Ok(())
+}
@@ -0,0 +1,214 @@
+//! Module providing parsing of Julia code with diagnostics, telling apart
+//! complete code, code which ends in the middle of an expression and code
+//! with syntax errors.
+//!
+//! Incomplete input is what an editor or REPL should keep reading more lines
+//! for, e.g. an unclosed `begin` block, while syntax errors come with the
+//! span of the code that couldn't be parsed.
+
+use std::fmt;
+use std::error;
+use std::convert::TryFrom;
+
+use sys::*;
+use error::Result;
+use string::IntoCString;
+use super::{Value, JlValue, Svec, Expr};
+
+/// A position in source code.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Position {
+    /// Byte offset from the start.
+    pub offset: usize,
+    /// Line number, starting at 1.
+    pub line: usize,
+    /// Column in characters, starting at 1.
+    pub column: usize,
+}
+
+impl Position {
+    /// Finds the line and column of a byte offset in code.
+    fn new(code: &str, offset: usize) -> Position {
+        let before = &code[..offset];
+        let line_start = before.rfind('\n').map(|i| i + 1).unwrap_or(0);
+        Position {
+            offset: offset,
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+        }
+    }
+}
+
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+/// Code that couldn't be parsed, with the message from the parser.
+#[derive(Clone, Debug)]
+pub struct SyntaxError {
+    /// The message from the parser.
+    pub message: String,
+    /// Start of the expression the parser failed on.
+    pub start: Position,
+    /// Where the parser stopped.
+    pub end: Position,
+}
+
+impl fmt::Display for SyntaxError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} (at {}-{})", self.message, self.start, self.end)
+    }
+}
+
+impl error::Error for SyntaxError {
+    fn description(&self) -> &str {
+        &self.message
+    }
+}
+
+/// The outcome of parsing code.
+#[derive(Clone, Debug)]
+pub enum Parsed<T> {
+    /// The code was parsed successfully.
+    Complete(T),
+    /// The code ends in the middle of an expression.
+    Incomplete(SyntaxError),
+    /// The code has a syntax error.
+    Error(SyntaxError),
+}
+
+impl<T> Parsed<T> {
+    /// Checks if the code was parsed successfully.
+    pub fn is_complete(&self) -> bool {
+        match *self {
+            Parsed::Complete(_) => true,
+            _ => false,
+        }
+    }
+
+    /// Checks if the code ends in the middle of an expression.
+    pub fn is_incomplete(&self) -> bool {
+        match *self {
+            Parsed::Incomplete(_) => true,
+            _ => false,
+        }
+    }
+
+    /// Returns the parsed code, if it was parsed successfully.
+    pub fn ok(self) -> Option<T> {
+        match self {
+            Parsed::Complete(x) => Some(x),
+            _ => None,
+        }
+    }
+
+    /// Turns incomplete code and syntax errors into Error::Syntax.
+    pub fn into_result(self) -> Result<T> {
+        match self {
+            Parsed::Complete(x) => Ok(x),
+            Parsed::Incomplete(err) |
+            Parsed::Error(err) => Err(::error::Error::Syntax(err)),
+        }
+    }
+
+    fn map<U, F: FnOnce(T) -> U>(self, f: F) -> Parsed<U> {
+        match self {
+            Parsed::Complete(x) => Parsed::Complete(f(x)),
+            Parsed::Incomplete(err) => Parsed::Incomplete(err),
+            Parsed::Error(err) => Parsed::Error(err),
+        }
+    }
+}
+
+/// Parses a single expression starting at byte offset `start` and returns it
+/// with the offset after it, or None if there's nothing but whitespace and
+/// comments left.
+fn parse_at(code: &str, start: usize) -> Result<Option<(Parsed<Value>, usize)>> {
+    let len = code.len();
+    let cstring = code.into_cstring();
+    let raw = unsafe { jl_parse_string(cstring.as_ptr(), len, start as _, 1) };
+    jl_catch!();
+
+    let ret = Svec::new(raw as *mut _)?;
+    let value = ret.index(0)?;
+    let end = isize::try_from(&ret.index(1)?)? as usize;
+    if value.lock()? == unsafe { jl_emptytuple } {
+        return Ok(None);
+    }
+
+    let head = if value.is_expr() {
+        Some(value.to_value::<_, Expr>()?.head_name()?)
+    } else {
+        None
+    };
+    let error = |expr: &Value| -> Result<SyntaxError> {
+        let expr: Expr = expr.to_value()?;
+        let message = expr.arg(0).map(|msg| {
+            String::try_from(&msg).unwrap_or_else(|_| msg.to_string())
+        })?;
+        // the expression starts after any whitespace.
+        let skipped = code[start..].len() - code[start..].trim_start().len();
+        Ok(SyntaxError {
+            message: message,
+            start: Position::new(code, (start + skipped).min(end)),
+            end: Position::new(code, end.min(len)),
+        })
+    };
+
+    let parsed = match head.as_ref().map(|head| &**head) {
+        Some("incomplete") => Parsed::Incomplete(error(&value)?),
+        Some("error") => Parsed::Error(error(&value)?),
+        _ => Parsed::Complete(value),
+    };
+    Ok(Some((parsed, end)))
+}
+
+/// Parses code consisting of a single expression.
+///
+/// Code that's empty or has more than one expression is a syntax error.
+pub fn parse(code: &str) -> Result<Parsed<Value>> {
+    let (parsed, end) = match parse_at(code, 0)? {
+        Some(ret) => ret,
+        None => {
+            return Ok(Parsed::Error(SyntaxError {
+                message: "no expression".to_string(),
+                start: Position::new(code, code.len()),
+                end: Position::new(code, code.len()),
+            }))
+        }
+    };
+
+    match parsed {
+        Parsed::Complete(value) => {
+            if parse_at(code, end)?.is_some() {
+                let start = end + (code[end..].len() - code[end..].trim_start().len());
+                return Ok(Parsed::Error(SyntaxError {
+                    message: "extra token after end of expression".to_string(),
+                    start: Position::new(code, start),
+                    end: Position::new(code, code.len()),
+                }));
+            }
+            Ok(Parsed::Complete(value))
+        }
+        parsed => Ok(parsed),
+    }
+}
+
+/// Parses code consisting of any number of expressions, e.g. a whole file.
+pub fn parse_all(code: &str) -> Result<Parsed<Vec<Value>>> {
+    let mut exprs = vec![];
+    let mut start = 0;
+    while let Some((parsed, end)) = parse_at(code, start)? {
+        match parsed {
+            Parsed::Complete(value) => exprs.push(value),
+            parsed => return Ok(parsed.map(|_| vec![])),
+        }
+        if end <= start {
+            break;
+        }
+        start = end;
+    }
+    Ok(Parsed::Complete(exprs))
+}
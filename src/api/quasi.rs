@@ -0,0 +1,91 @@
+//! Module providing the `julia!` macro, which evaluates Julia code written
+//! inline in Rust, with Rust values interpolated into it.
+//!
+//! ```ignore
+//! let xs = jl.eval_string("[1.0, 2.0, 3.0]")?;
+//! let n = 3.0;
+//! let mean = julia!("sum($xs) / $n", xs, n)?;
+//! let scaled = julia!("$xs .* $k", xs, k = n * 2.0)?;
+//! ```
+//!
+//! The code is a string literal, followed by the Rust values interpolated
+//! into it: `name` passes the variable `name` as `$name`, and
+//! `name = expr` passes the value of any Rust expression. The code is parsed
+//! the first time it runs and turned into a Julia function of the arguments,
+//! so they're passed to Julia as values and never formatted into source code.
+//!
+//! Interpolating anything but the name of an argument, e.g. `$(x + 1)`, is
+//! an error, and so is interpolating a name that isn't passed. `$` inside of
+//! Julia string literals is Julia's own string interpolation.
+
+use std::cell::RefCell;
+use std::thread::LocalKey;
+
+use error::Result;
+use super::{Value, JlValue, Function};
+use super::glue;
+use super::module::parse_input;
+
+/// Evaluates inline Julia code and returns a `Result<Value>`, see the
+/// module documentation.
+#[macro_export]
+macro_rules! julia {
+    ($code:literal $(, $name:ident $(= $value:expr)?)* $(,)?) => {
+        {
+            thread_local! {
+                static COMPILED: ::std::cell::RefCell<Option<$crate::api::Function>> =
+                    ::std::cell::RefCell::new(None);
+            }
+
+            $crate::api::quasi::eval(
+                &COMPILED,
+                $code,
+                &[$(stringify!($name)),*],
+                &[$($crate::api::quasi::interpolate(&__julia_arg!($name $(= $value)?))),*],
+            )
+        }
+    };
+}
+
+/// Selects the value of an argument of `julia!`.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __julia_arg {
+    ($name:ident) => {
+        $name
+    };
+    ($name:ident = $value:expr) => {
+        $value
+    };
+}
+
+/// Converts an interpolated Rust value.
+#[doc(hidden)]
+pub fn interpolate<T: Clone + Into<Value>>(value: &T) -> Value {
+    value.clone().into()
+}
+
+/// Evaluates the code of a `julia!` invocation with the values of its
+/// arguments, compiling it first if it didn't run on this thread yet.
+#[doc(hidden)]
+pub fn eval(
+    compiled: &'static LocalKey<RefCell<Option<Function>>>,
+    code: &str,
+    names: &[&str],
+    values: &[Value],
+) -> Result<Value> {
+    let function = compiled.with(|compiled| -> Result<Function> {
+        if let Some(ref function) = *compiled.borrow() {
+            return Ok(function.clone());
+        }
+
+        let parsed = parse_input(code, "julia!")?;
+        let names: Vec<Value> = names.iter().map(|name| Value::from(*name)).collect();
+        let quasi = glue::function("quasi")?;
+        let function = quasi.call(Some(&parsed).into_iter().chain(&names))?;
+        let function = Function::from_value(function)?;
+        *compiled.borrow_mut() = Some(function.clone());
+        Ok(function)
+    })?;
+    function.call(values)
+}
@@ -0,0 +1,157 @@
+//! Module providing hot reloading of Julia source files.
+//!
+//! A Reloader remembers which files were loaded into which modules and
+//! includes them again when their modification time changes. Changes are
+//! found by comparing modification times whenever `Reloader::poll` is called,
+//! not through notifications from the OS, so it has to be called regularly,
+//! e.g. from the host's event loop. Functions and methods defined in a
+//! reloaded file replace the previous definitions, so Rust code calling them
+//! through their module picks up the new ones.
+//!
+//! A file is parsed before it's evaluated, so a reload failing with a syntax
+//! error keeps all of the previous definitions. A reload failing at runtime
+//! can't be undone, since the file is evaluated into the module itself: the
+//! expressions before the failing one have already replaced their previous
+//! definitions, while everything after it keeps the definitions of the last
+//! load. The module stays in that mixed state until the file is fixed and
+//! reloaded, see `Reload::load_error` for where it failed.
+
+use std::env;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use error::{Result, Error};
+use super::{Julia, Value, Module, LoadError};
+use super::glue;
+
+/// The outcome of loading a watched file.
+pub struct Reload {
+    /// The file that was loaded.
+    pub path: PathBuf,
+    /// The module it was loaded into.
+    pub module: Module,
+    /// The value of the file's last expression, or the error that stopped
+    /// it from loading.
+    pub result: Result<Value>,
+}
+
+impl Reload {
+    /// Checks if the file was loaded successfully.
+    pub fn is_ok(&self) -> bool {
+        self.result.is_ok()
+    }
+
+    /// Returns the file, line and underlying error of a failed reload, if it
+    /// failed with a LoadError.
+    pub fn load_error(&self) -> Option<LoadError> {
+        match self.result {
+            Err(Error::UnhandledException(ref ex)) => ex.load_error().ok().and_then(|err| err),
+            _ => None,
+        }
+    }
+}
+
+/// A file loaded into a module.
+struct Watched {
+    path: PathBuf,
+    module: Module,
+    modified: Option<SystemTime>,
+}
+
+/// Watches Julia source files and reloads them into their modules when they
+/// change.
+#[derive(Default)]
+pub struct Reloader {
+    files: Vec<Watched>,
+    callbacks: Vec<Box<dyn FnMut(&Reload)>>,
+}
+
+impl Reloader {
+    /// Creates a Reloader without any files.
+    pub fn new() -> Reloader {
+        Reloader::default()
+    }
+
+    /// Loads a file into a module and reloads it whenever it changes.
+    pub fn watch<P: AsRef<Path>>(&mut self, jl: &mut Julia, path: P, module: &Module) -> Result<Value> {
+        let path = path.as_ref();
+        let path = if path.is_absolute() {
+            path.to_owned()
+        } else {
+            env::current_dir()?.join(path)
+        };
+
+        let modified = modified(jl, &path);
+        let ret = load(jl, &path, module);
+        self.unwatch(&path);
+        self.files.push(Watched {
+            path: path,
+            module: module.clone(),
+            modified: modified,
+        });
+        ret
+    }
+
+    /// Stops watching a file, keeping what it defined.
+    pub fn unwatch<P: AsRef<Path>>(&mut self, path: P) {
+        let path = path.as_ref();
+        self.files.retain(|file| file.path != path);
+    }
+
+    /// Returns the watched files.
+    pub fn files(&self) -> Vec<&Path> {
+        self.files.iter().map(|file| &*file.path).collect()
+    }
+
+    /// Registers a callback called after every reload, whether it succeeded
+    /// or not.
+    pub fn on_reload<F: FnMut(&Reload) + 'static>(&mut self, callback: F) {
+        self.callbacks.push(Box::new(callback));
+    }
+
+    /// Reloads the files which were modified since they were last loaded and
+    /// returns the outcomes. Doesn't block, nothing is reloaded between calls.
+    ///
+    /// Files which can't be accessed, e.g. because they're being saved, are
+    /// checked again the next time.
+    pub fn poll(&mut self, jl: &mut Julia) -> Vec<Reload> {
+        let mut reloads = vec![];
+        for file in &mut self.files {
+            let modified = match modified(jl, &file.path) {
+                Some(modified) => modified,
+                None => continue,
+            };
+            if file.modified == Some(modified) {
+                continue;
+            }
+            file.modified = Some(modified);
+
+            let result = load(jl, &file.path, &file.module);
+            let reload = Reload {
+                path: file.path.clone(),
+                module: file.module.clone(),
+                result: result,
+            };
+            for callback in &mut self.callbacks {
+                callback(&reload);
+            }
+            reloads.push(reload);
+        }
+        reloads
+    }
+}
+
+/// Returns the modification time of a file.
+fn modified(jl: &Julia, path: &Path) -> Option<SystemTime> {
+    let path = path.to_string_lossy().into_owned();
+    jl.stat(path).ok().map(|stat| stat.modified)
+}
+
+/// Checks the syntax of a file and includes it into a module.
+fn load(jl: &mut Julia, path: &Path, module: &Module) -> Result<Value> {
+    let check_syntax = glue::function("check_syntax")?;
+    let path_value = Value::from(&*path.to_string_lossy());
+    let ret = check_syntax.call1(&path_value).and_then(|_| module.include(path));
+    jl.flush_output()?;
+    ret
+}
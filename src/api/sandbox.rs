@@ -0,0 +1,285 @@
+//! Module providing sandboxes for evaluating untrusted snippets of Julia code.
+//!
+//! A sandbox is a fresh anonymous module which doesn't use Base, so nothing
+//! but Core and the bindings explicitly allowed from Rust is available in it.
+//! Code is parsed and checked before it's evaluated, rejecting anything that
+//! could reach outside of the module, like qualified names, `ccall`, `eval`,
+//! macros, `import`, module definitions or methods added to functions from
+//! other modules. Evaluation is interrupted if it takes longer than the
+//! timeout.
+//!
+//! The check is syntactic, so allowed functions must be safe to call with any
+//! arguments, e.g. allowing `Base.run` or `Base.open` defeats the sandbox.
+
+use std::collections::HashSet;
+use std::convert::TryFrom;
+use std::time::Duration;
+
+use error::{Result, Error};
+use super::{Julia, Value, JlValue, Module, Symbol, Expr, ExprVisitor, InterruptHandle};
+use super::base_function;
+use super::glue;
+use super::expr::walk_value;
+use super::interrupt::with_timeout;
+use super::module::parse_input;
+
+/// Expression heads allowed in sandboxed code.
+const ALLOWED_HEADS: &[&str] = &[
+    "toplevel", "block", "line", "call", "=", "+=", "-=", "*=", "/=", "tuple",
+    "vect", "vcat", "hcat", "row", "ref", "if", "elseif", "&&", "||", "while",
+    "for", "break", "continue", "return", "function", "->", "local", "const",
+    "comparison", "string", "kw", "parameters", "let", "generator",
+    "comprehension", "filter", "...", "::", "curly", "where",
+    // syntax errors, which are thrown once evaluated.
+    "error", "incomplete",
+];
+
+/// Names which are rejected anywhere in sandboxed code, because they give
+/// access to other modules, raw memory or code evaluation.
+const DENIED_NAMES: &[&str] = &[
+    "ccall", "cglobal", "llvmcall", "eval", "include", "Main", "Base", "Core",
+    "Intrinsics", "getfield", "setfield!", "getproperty", "setproperty!",
+    "invoke", "pointerref", "pointerset", "unsafe_load", "unsafe_store!",
+];
+
+/// An isolated module for evaluating untrusted code.
+pub struct Sandbox {
+    module: Module,
+    interrupt: InterruptHandle,
+    timeout: Option<Duration>,
+    denied: HashSet<String>,
+}
+
+impl Sandbox {
+    /// Creates an empty sandbox without a timeout.
+    pub fn new(jl: &Julia) -> Result<Sandbox> {
+        let module = Module::new(Symbol::gensym(), None)?;
+        // the module isn't bound anywhere, so it's only referenced from Rust.
+        let root = glue::function("root")?;
+        root.call1(&module.to_value()?)?;
+        Ok(Sandbox {
+            module: module,
+            interrupt: jl.interrupt_handle(),
+            timeout: None,
+            denied: DENIED_NAMES.iter().map(|name| name.to_string()).collect(),
+        })
+    }
+
+    /// Returns the module code is evaluated in. It's only kept alive as long
+    /// as the sandbox is.
+    pub fn module(&self) -> &Module {
+        &self.module
+    }
+
+    /// Returns the time evaluation may take, if limited.
+    pub fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    /// Limits the time evaluation may take.
+    pub fn set_timeout(&mut self, timeout: Option<Duration>) {
+        self.timeout = timeout;
+    }
+
+    /// Binds value to name as a constant in the sandbox.
+    ///
+    /// Allowing a name which is denied by default, like `getfield`, makes it
+    /// usable in sandboxed code.
+    pub fn allow(&mut self, name: &str, value: &Value) -> Result<()> {
+        self.module.set_const(name, value)?;
+        self.denied.remove(name);
+        Ok(())
+    }
+
+    /// Binds the global name of another module in the sandbox, e.g. a
+    /// function like `+` from Base.
+    pub fn allow_from(&mut self, from: &Module, name: &str) -> Result<()> {
+        let value = from.global(name)?;
+        self.allow(name, &value)
+    }
+
+    /// Rejects code using name, in addition to the names denied by default.
+    pub fn deny(&mut self, name: &str) {
+        self.denied.insert(name.to_string());
+    }
+
+    /// Parses code and checks it only uses allowed constructs, without
+    /// evaluating it. Returns the parsed code.
+    ///
+    /// ## Errors
+    ///
+    /// Returns Error::Disallowed if the code uses anything that isn't allowed.
+    pub fn check(&self, code: &str) -> Result<Value> {
+        let parsed = parse_input(code, "sandbox")?;
+        let mut checker = Checker {
+            module: &self.module,
+            denied: &self.denied,
+            assigned: HashSet::new(),
+            methods: HashSet::new(),
+        };
+        walk_value(&parsed, &mut checker)?;
+
+        // the binding of a name assigned in the same code can't be checked
+        // before it's evaluated, e.g. `const plus = +; plus(a, b) = 0`.
+        if let Some(name) = checker.methods.intersection(&checker.assigned).next() {
+            return Err(Error::Disallowed(format!("methods of `{}`", name)));
+        }
+        Ok(parsed)
+    }
+
+    /// Checks and evaluates code in the sandbox.
+    ///
+    /// ## Errors
+    ///
+    /// Returns Error::Disallowed if the code uses anything that isn't allowed
+    /// and Error::Timeout if it took too long.
+    pub fn eval(&self, code: &str) -> Result<Value> {
+        let parsed = self.check(code)?;
+
+        let eval = || if parsed.is_expr() {
+            self.module.eval(&parsed.to_value()?)
+        } else {
+            // a lone literal or name.
+            self.module.eval_string(code)
+        };
+        match self.timeout {
+            Some(timeout) => with_timeout(&self.interrupt, timeout, eval),
+            None => eval(),
+        }
+    }
+}
+
+impl Drop for Sandbox {
+    fn drop(&mut self) {
+        if let (Ok(unroot), Ok(module)) = (glue::function("unroot"), self.module.to_value::<_, Value>()) {
+            let _ = unroot.call1(&module);
+        }
+    }
+}
+
+/// Rejects disallowed constructs while walking parsed code.
+struct Checker<'a> {
+    module: &'a Module,
+    denied: &'a HashSet<String>,
+    /// Names assigned to.
+    assigned: HashSet<String>,
+    /// Names methods are defined for.
+    methods: HashSet<String>,
+}
+
+impl<'a> Checker<'a> {
+    /// Checks that a method definition only adds methods to a function owned
+    /// by the sandbox, i.e. a plain name which is either unbound or bound to
+    /// a function defined in the sandbox. Anything else, e.g. `+` allowed
+    /// from Base, a type from Core or `(::Type{Int64})(x)`, would change
+    /// code outside of the sandbox.
+    fn check_method(&mut self, callee: &Value) -> Result<()> {
+        if !callee.is_symbol() {
+            return Err(Error::Disallowed("methods of anything but plain names".to_string()));
+        }
+
+        let name: Symbol = callee.to_value()?;
+        let module: Value = self.module.to_value()?;
+        let binding_module = base_function("binding_module")?;
+        let owner = binding_module.call2(&module, callee)?;
+
+        let mut owned = owner.lock()? == module.lock()?;
+        if owned && self.module.is_defined(name.clone())? {
+            // e.g. `const plus = +` doesn't make `+` the sandbox's own.
+            let function = self.module.global(name.clone())?;
+            owned = function.datatype()?.module()?.lock()? == self.module.lock()?;
+        }
+        let name = String::try_from(&name)?;
+        if !owned {
+            return Err(Error::Disallowed(format!("methods of `{}`", name)));
+        }
+        self.methods.insert(name);
+        Ok(())
+    }
+
+    /// Records the names assigned to by the left-hand side of an assignment,
+    /// e.g. `a` and `b` for `(a, b::Int) = x`.
+    fn assign(&mut self, lhs: &Value) -> Result<()> {
+        if lhs.is_symbol() {
+            let name = String::try_from(&lhs.to_value::<_, Symbol>()?)?;
+            self.assigned.insert(name);
+        } else if lhs.is_expr() {
+            let lhs: Expr = lhs.to_value()?;
+            match &*lhs.head_name()? {
+                "tuple" => {
+                    for arg in lhs.args()? {
+                        self.assign(&arg)?;
+                    }
+                }
+                "::" if lhs.nargs()? == 2 => self.assign(&lhs.arg(0)?)?,
+                _ => (),
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Returns the function a method is defined for from the signature of a
+/// method definition, e.g. `f` for `f(x::T)::T where T`, or None if it isn't
+/// one.
+fn method_callee(sig: &Value) -> Result<Option<Value>> {
+    if !sig.is_expr() {
+        return Ok(None);
+    }
+
+    let sig: Expr = sig.to_value()?;
+    match &*sig.head_name()? {
+        "call" => sig.arg(0).map(Some),
+        // a return type, e.g. `f(x)::Int`, or type parameters.
+        "where" => method_callee(&sig.arg(0)?),
+        "::" if sig.nargs()? == 2 => method_callee(&sig.arg(0)?),
+        _ => Ok(None),
+    }
+}
+
+impl<'a> ExprVisitor for Checker<'a> {
+    fn visit_expr(&mut self, expr: &Expr) -> Result<bool> {
+        let head = expr.head_name()?;
+        let allowed = match &*head {
+            // broadcasting calls, e.g. `f.(x)`, but not field access.
+            "." => {
+                let args = expr.args()?;
+                args.len() == 2 && args[1].is_expr() &&
+                    args[1].to_value::<_, Expr>()?.head_name()? == "tuple"
+            }
+            head => ALLOWED_HEADS.contains(&head),
+        };
+        if !allowed {
+            return Err(Error::Disallowed(format!("`{}` expressions", head)));
+        }
+
+        if (head == "=" || head == "function") && expr.nargs()? > 0 {
+            let sig = expr.arg(0)?;
+            if let Some(callee) = method_callee(&sig)? {
+                self.check_method(&callee)?;
+            } else if head == "function" && expr.nargs()? == 1 {
+                // `function f end`
+                self.check_method(&sig)?;
+            } else if head == "=" {
+                self.assign(&sig)?;
+            }
+        }
+        Ok(true)
+    }
+
+    fn visit_symbol(&mut self, sym: &Symbol) -> Result<()> {
+        let name = String::try_from(sym)?;
+        if self.denied.contains(&name) {
+            return Err(Error::Disallowed(format!("use of `{}`", name)));
+        }
+        Ok(())
+    }
+
+    fn visit_value(&mut self, value: &Value) -> Result<()> {
+        // literals, quoted values and line numbers are inert.
+        if value.is_globalref() {
+            return Err(Error::Disallowed("references to other modules".to_string()));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,268 @@
+//! Module providing functions for creating, saving and restoring system
+//! images.
+//!
+//! A system image is a snapshot of the Julia heap, including all loaded
+//! modules and compiled code. Starting Julia from an image with packages
+//! already loaded avoids loading and compiling them again at every startup.
+
+use std::env;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::ffi::{CStr, CString};
+use std::path::{Path, PathBuf};
+use std::ptr;
+use std::slice;
+use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use sys::*;
+use error::{Result, Error};
+use string::IntoCString;
+use super::{Julia, Value, JlValue, Expr, IntoSymbol};
+
+/// Returns the directory containing libjulia, which Julia uses as its home
+/// directory when embedded.
+#[cfg(unix)]
+fn julia_home() -> Result<CString> {
+    let mut info: ::libc::Dl_info = unsafe { ::std::mem::zeroed() };
+    let found = unsafe { ::libc::dladdr(jl_init as *const ::libc::c_void, &mut info) };
+    if found == 0 || info.dli_fname.is_null() {
+        return Err(Error::NullPointer);
+    }
+
+    let lib = unsafe { CStr::from_ptr(info.dli_fname) }.to_string_lossy();
+    let home = PathBuf::from(&*lib)
+        .parent()
+        .map(|dir| dir.to_string_lossy().into_owned())
+        .unwrap_or_else(|| String::from("."));
+    Ok(home.into_cstring())
+}
+
+/// Returns the directory containing libjulia, which Julia uses as its home
+/// directory when embedded.
+#[cfg(not(unix))]
+fn julia_home() -> Result<CString> {
+    Err(Error::NullPointer)
+}
+
+impl Julia {
+    /// Returns the path of the default system image, relative to the Julia
+    /// home directory.
+    pub fn default_image_path() -> Result<String> {
+        let path = unsafe { jl_get_default_sysimg_path() };
+        if path.is_null() {
+            return Err(Error::NullPointer);
+        }
+        let path = unsafe { CStr::from_ptr(path) };
+        Ok(path.to_string_lossy().into_owned())
+    }
+
+    /// Initialize the Julia runtime from a system image file. Relative paths
+    /// are resolved against the directory containing libjulia.
+    ///
+    /// ## Errors
+    ///
+    /// Returns Error::JuliaInitialized if Julia is already initialized.
+    pub fn with_image<S: IntoCString>(image: S) -> Result<Julia> {
+        let home = julia_home()?;
+        Julia::with_image_in(home, image)
+    }
+
+    /// Initialize the Julia runtime from a system image file, using `home`
+    /// as the Julia home directory. Relative paths are resolved against it.
+    ///
+    /// ## Errors
+    ///
+    /// Returns Error::JuliaInitialized if Julia is already initialized.
+    pub fn with_image_in<S: IntoCString, T: IntoCString>(home: S, image: T) -> Result<Julia> {
+        if Julia::is_initialized() {
+            return Err(Error::JuliaInitialized);
+        }
+
+        // Julia keeps both pointers in jl_options for the whole session.
+        let home = home.into_cstring().into_raw();
+        let image = image.into_cstring().into_raw();
+        unsafe {
+            jl_init_with_image(home, image);
+        }
+        jl_catch!();
+
+        let mut jl = unsafe { Julia::new_unchecked() };
+        jl.at_exit(Some(0));
+        Ok(jl)
+    }
+
+    /// Initialize the Julia runtime from a system image in memory, e.g. one
+    /// produced by `ImageBuilder::to_bytes`.
+    ///
+    /// The image is written to a new temporary file, readable only by the
+    /// current user, which is removed once Julia was initialized or failed
+    /// to be. `jl_restore_system_image_data` can't be used instead, since
+    /// Julia only restores images in the middle of its own initialization,
+    /// and then only from the file in `jl_options`.
+    ///
+    /// ## Errors
+    ///
+    /// Returns Error::JuliaInitialized if Julia is already initialized.
+    pub fn with_image_data(data: &[u8]) -> Result<Julia> {
+        if Julia::is_initialized() {
+            return Err(Error::JuliaInitialized);
+        }
+
+        let image = TempImage::create(data)?;
+        let path = image.path().to_string_lossy().into_owned();
+        // the image is fully read during initialization.
+        Julia::with_image(path)
+    }
+}
+
+/// A temporary image file, removed when dropped.
+struct TempImage {
+    path: PathBuf,
+}
+
+impl TempImage {
+    /// Writes `data` to a newly created file with a unique name in the
+    /// temporary directory.
+    fn create(data: &[u8]) -> Result<TempImage> {
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+        loop {
+            let nanos = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|since| since.subsec_nanos())
+                .unwrap_or(0);
+            let count = COUNTER.fetch_add(1, Ordering::SeqCst);
+
+            let mut path = env::temp_dir();
+            path.push(format!("julia-rs-{}-{}-{}.ji", process::id(), nanos, count));
+
+            // create_new fails instead of following a symlink or reusing a
+            // file someone else created with the same name.
+            let mut file = match open_private(&path) {
+                Ok(file) => file,
+                Err(ref err) if err.kind() == io::ErrorKind::AlreadyExists => continue,
+                Err(err) => return Err(From::from(err)),
+            };
+
+            // from here on the file is removed on every path.
+            let image = TempImage { path: path };
+            file.write_all(data)?;
+            file.sync_all()?;
+            return Ok(image);
+        }
+    }
+
+    fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for TempImage {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+#[cfg(unix)]
+fn open_private(path: &Path) -> io::Result<File> {
+    use std::os::unix::fs::OpenOptionsExt;
+    OpenOptions::new().write(true).create_new(true).mode(0o600).open(path)
+}
+
+#[cfg(not(unix))]
+fn open_private(path: &Path) -> io::Result<File> {
+    OpenOptions::new().write(true).create_new(true).open(path)
+}
+
+/// A Julia runtime initialized in output mode, from which a system image can
+/// be created.
+///
+/// Images created from a runtime started normally might not be restorable,
+/// so the builder has to be created before Julia is initialized in any
+/// other way.
+pub struct ImageBuilder {
+    jl: Julia,
+}
+
+impl ImageBuilder {
+    /// Initializes Julia in output mode.
+    ///
+    /// ## Errors
+    ///
+    /// Returns Error::JuliaInitialized if Julia is already initialized.
+    pub fn new() -> Result<ImageBuilder> {
+        if Julia::is_initialized() {
+            return Err(Error::JuliaInitialized);
+        }
+
+        // any non-null outputji turns on output mode; it's reset before
+        // exiting so that Julia doesn't try to write the image itself.
+        unsafe {
+            jl_options.outputji = b"\0".as_ptr() as *const _;
+        }
+        let jl = Julia::new()?;
+
+        Ok(ImageBuilder { jl: jl })
+    }
+
+    /// Returns a reference to the runtime.
+    pub fn julia(&self) -> &Julia {
+        &self.jl
+    }
+
+    /// Returns a mutable reference to the runtime, e.g. for evaluating setup
+    /// code to include in the image.
+    pub fn julia_mut(&mut self) -> &mut Julia {
+        &mut self.jl
+    }
+
+    /// Loads a module with `using` so that it's part of the image. Submodules
+    /// are separated by dots, e.g. `"Base.Test"`.
+    pub fn preload(&mut self, module: &str) -> Result<&mut ImageBuilder> {
+        // the path is built as symbols, so it's never parsed as code.
+        let path = module
+            .split('.')
+            .map(|name| Value::from_value(name.into_symbol()?))
+            .collect::<Result<Vec<_>>>()?;
+        let using = Expr::new("using", &path)?;
+        self.jl.main().eval(&using)?;
+        Ok(self)
+    }
+
+    /// Writes the image to a file.
+    pub fn save<S: IntoCString>(&mut self, path: S) -> Result<()> {
+        let path = path.into_cstring();
+        unsafe {
+            jl_save_system_image(path.as_ptr());
+        }
+        jl_catch!();
+        Ok(())
+    }
+
+    /// Returns the image as bytes.
+    pub fn to_bytes(&mut self) -> Result<Vec<u8>> {
+        let ios = unsafe { jl_create_system_image() };
+        jl_catch!();
+        if ios.is_null() {
+            return Err(Error::NullPointer);
+        }
+
+        unsafe {
+            let data = slice::from_raw_parts((*ios).buf as *const u8, (*ios).size as usize);
+            let data = data.to_vec();
+            ios_close(ios);
+            ::libc::free(ios as *mut ::libc::c_void);
+            Ok(data)
+        }
+    }
+}
+
+impl Drop for ImageBuilder {
+    fn drop(&mut self) {
+        unsafe {
+            jl_options.outputji = ptr::null();
+        }
+    }
+}
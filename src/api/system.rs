@@ -0,0 +1,159 @@
+//! Module providing information about the host system as seen by Julia and
+//! access to the system functions used by its runtime.
+
+use std::fmt;
+use std::io;
+use std::mem;
+use std::ffi::CStr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use sys::*;
+use error::Result;
+use string::IntoCString;
+use version::Version;
+use super::Julia;
+
+/// Information about the Julia runtime and the system it runs on.
+#[derive(Clone)]
+pub struct SystemInfo<'a> {
+    /// Version of the Julia runtime.
+    pub version: Version<'a>,
+    /// Name of the operating system, as in `Sys.KERNEL`.
+    pub os: String,
+    /// Name of the CPU architecture, as in `Sys.ARCH`.
+    pub arch: String,
+    /// Number of logical CPU cores.
+    pub cpu_cores: u32,
+    /// Size of a memory page in bytes.
+    pub page_size: usize,
+    /// Granularity of memory allocations in bytes.
+    pub allocation_granularity: usize,
+    /// Is this a debug build of Julia?
+    pub debug_build: bool,
+    /// Tag of the CPU features the system image was built for, if it was
+    /// built for a specific CPU.
+    pub cpuid_tag: Option<u64>,
+}
+
+impl<'a> fmt::Display for SystemInfo<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.version)?;
+        if self.debug_build {
+            write!(f, " (debug build)")?;
+        }
+        writeln!(f)?;
+        writeln!(f, "  OS: {} ({})", self.os, self.arch)?;
+        writeln!(f, "  CPU cores: {}", self.cpu_cores)?;
+        writeln!(f, "  Page size: {} bytes", self.page_size)?;
+        write!(
+            f,
+            "  Allocation granularity: {} bytes",
+            self.allocation_granularity
+        )?;
+        if let Some(tag) = self.cpuid_tag {
+            write!(f, "\n  CPUID tag: {:#x}", tag)?;
+        }
+        Ok(())
+    }
+}
+
+/// Status of a file, as returned by `Julia::stat`.
+#[derive(Clone, Copy, Debug)]
+pub struct Stat {
+    /// File type and permissions.
+    pub mode: u64,
+    /// Size in bytes.
+    pub size: u64,
+    /// Time of the last modification.
+    pub modified: SystemTime,
+}
+
+impl Julia {
+    /// Returns information about the runtime and the system it runs on.
+    pub fn system_info(&self) -> SystemInfo {
+        unsafe {
+            let os = CStr::from_ptr(jl_symbol_name(jl_get_UNAME()));
+            let arch = CStr::from_ptr(jl_symbol_name(jl_get_ARCH()));
+            let cpuid_tag = if jl_uses_cpuid_tag() != 0 {
+                Some(jl_cpuid_tag() as u64)
+            } else {
+                None
+            };
+
+            SystemInfo {
+                version: self.version(),
+                os: os.to_string_lossy().into_owned(),
+                arch: arch.to_string_lossy().into_owned(),
+                cpu_cores: jl_cpu_cores() as u32,
+                page_size: jl_getpagesize() as usize,
+                allocation_granularity: jl_getallocationgranularity() as usize,
+                debug_build: jl_is_debugbuild() != 0,
+                cpuid_tag: cpuid_tag,
+            }
+        }
+    }
+
+    /// Returns the value of errno as seen by the runtime.
+    pub fn errno(&self) -> i32 {
+        unsafe { jl_errno() as i32 }
+    }
+
+    /// Sets the value of errno as seen by the runtime.
+    pub fn set_errno(&mut self, errno: i32) {
+        unsafe {
+            jl_set_errno(errno);
+        }
+    }
+
+    /// Queries the status of a file the same way Julia's `stat` does.
+    ///
+    /// ## Errors
+    ///
+    /// Returns Error::IOError if the file couldn't be queried.
+    pub fn stat<S: IntoCString>(&self, path: S) -> Result<Stat> {
+        let path = path.into_cstring();
+        let mut stat: uv_stat_t = unsafe { mem::zeroed() };
+        let ret = unsafe { jl_stat(path.as_ptr(), &mut stat as *mut uv_stat_t as *mut _) };
+        if ret != 0 {
+            // libuv returns negated errno values.
+            return Err(From::from(io::Error::from_raw_os_error(-ret)));
+        }
+
+        let modified = system_time(stat.st_mtim.tv_sec as i64, stat.st_mtim.tv_nsec as u32)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "modification time out of range"))?;
+        Ok(Stat {
+            mode: stat.st_mode,
+            size: stat.st_size,
+            modified: modified,
+        })
+    }
+}
+
+/// Converts a time in seconds and nanoseconds since the epoch, which may be
+/// before it, or None if it can't be represented.
+fn system_time(secs: i64, nanos: u32) -> Option<SystemTime> {
+    if secs >= 0 {
+        UNIX_EPOCH.checked_add(Duration::new(secs as u64, nanos))
+    } else {
+        // the nanoseconds still count forward, e.g. -2s + 0.5s.
+        UNIX_EPOCH
+            .checked_sub(Duration::from_secs(secs.unsigned_abs()))?
+            .checked_add(Duration::from_nanos(nanos as u64))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use super::system_time;
+
+    #[test]
+    fn before_epoch() {
+        assert_eq!(system_time(5, 0), Some(UNIX_EPOCH + Duration::from_secs(5)));
+        assert_eq!(system_time(-2, 500_000_000), Some(UNIX_EPOCH - Duration::from_millis(1500)));
+        // too far out to represent, but no panic.
+        system_time(i64::max_value(), 999_999_999);
+        system_time(i64::min_value(), 0);
+    }
+}
@@ -1,19 +1,142 @@
-
 //! Module providing a wrapper for the native Julia task object.
 
+use std::ffi::CStr;
+
 use sys::*;
-use error::Result;
-use api::{JlValue, Function};
+use error::{Result, Error};
+use api::{Value, JlValue, Function, Exception};
+use api::base_function;
 
 jlvalues! {
     pub struct Task(jl_task_t);
 }
 
+/// The state of a Task.
+#[derive(Clone, Copy, Hash, PartialEq, Eq, Debug)]
+pub enum TaskState {
+    /// The Task can be run.
+    Runnable,
+    /// The Task is blocked, waiting for something.
+    Waiting,
+    /// The Task is in the scheduler's queue.
+    Queued,
+    /// The Task finished successfully.
+    Done,
+    /// The Task finished with an exception.
+    Failed,
+}
+
 impl Task {
-    /// Construct a new Task with a Function.
-    pub fn with_function(&self, start: &Function) -> Result<Task> {
+    /// Construct a new Task with a Function. The Task isn't scheduled until
+    /// `schedule` is called.
+    pub fn with_function(start: &Function) -> Result<Task> {
         let raw = unsafe { jl_new_task(start.lock()?, 0) };
         jl_catch!();
         Task::new(raw)
     }
+
+    /// Returns the Task as a generic Value, sharing the inner pointer.
+    fn as_value(&self) -> Result<Value> {
+        let raw = self.lock()?;
+        Value::new(raw as *mut jl_value_t)
+    }
+
+    /// Adds the Task to the scheduler's queue.
+    pub fn schedule(&self) -> Result<()> {
+        let schedule = base_function("schedule")?;
+        schedule.call1(&self.as_value()?)?;
+        Ok(())
+    }
+
+    /// Blocks the current Task until this one is done and returns its result.
+    ///
+    /// ## Errors
+    ///
+    /// Returns the exception the Task failed with, if it failed.
+    pub fn wait(&self) -> Result<Value> {
+        let wait = base_function("wait")?;
+        wait.call1(&self.as_value()?)
+    }
+
+    /// Waits for the Task to finish and fetches its result.
+    ///
+    /// ## Errors
+    ///
+    /// Returns the exception the Task failed with, if it failed.
+    pub fn fetch(&self) -> Result<Value> {
+        let fetch = base_function("fetch")?;
+        fetch.call1(&self.as_value()?)
+    }
+
+    /// Returns the state of the Task.
+    ///
+    /// ## Errors
+    ///
+    /// Returns Error::InvalidUnbox if the state isn't one of the known ones.
+    pub fn state(&self) -> Result<TaskState> {
+        let raw = self.lock()?;
+        let state = unsafe { CStr::from_ptr(jl_symbol_name((*raw).state)) };
+        let state = match state.to_bytes() {
+            b"runnable" => TaskState::Runnable,
+            b"waiting" => TaskState::Waiting,
+            b"queued" => TaskState::Queued,
+            b"done" => TaskState::Done,
+            b"failed" => TaskState::Failed,
+            _ => return Err(Error::InvalidUnbox),
+        };
+        Ok(state)
+    }
+
+    /// Checks if the Task has finished, either successfully or not.
+    pub fn is_done(&self) -> Result<bool> {
+        self.state().map(|state| {
+            state == TaskState::Done || state == TaskState::Failed
+        })
+    }
+
+    /// Checks if the Task has finished with an exception.
+    pub fn is_failed(&self) -> Result<bool> {
+        self.state().map(|state| state == TaskState::Failed)
+    }
+
+    /// Checks if the Task has started running.
+    pub fn is_started(&self) -> Result<bool> {
+        let raw = self.lock()?;
+        Ok(unsafe { (*raw).started() != 0 })
+    }
+
+    /// Returns the result of a finished Task, or nothing if it's not done yet.
+    pub fn result(&self) -> Result<Value> {
+        let raw = self.lock()?;
+        let result = unsafe { (*raw).result };
+        Value::new(result)
+    }
+
+    /// Returns the exception the Task failed with, if any.
+    pub fn exception(&self) -> Result<Option<Exception>> {
+        let raw = self.lock()?;
+        let ex = unsafe { (*raw).exception };
+        let ex = Value::new(ex)?;
+        if ex.is_nothing() {
+            Ok(None)
+        } else {
+            Exception::with_value(ex).map(Some)
+        }
+    }
+}
+
+/// Returns the currently running Task.
+pub fn current_task() -> Result<Task> {
+    let raw = unsafe { (*jl_get_ptls_states()).current_task };
+    Task::new(raw)
+}
+
+/// Switches to the next runnable Task, if any, and reschedules the current
+/// one.
+pub fn yield_now() -> Result<()> {
+    unsafe {
+        jl_yield();
+    }
+    jl_catch!();
+    Ok(())
 }
@@ -0,0 +1,67 @@
+//! Module providing guards for managing the GC state of threads running Julia
+//! code.
+//!
+//! A thread that runs Julia code is in the GC-unsafe state, meaning the
+//! garbage collector has to wait for it to reach a safepoint. Long-running
+//! Rust code which doesn't touch any Julia values should run in the GC-safe
+//! state, so that it doesn't stall collections triggered by other threads.
+//!
+//! Threads which weren't started by Julia can't be adopted, i.e. made able to
+//! run Julia code: Julia 0.6 has no `jl_adopt_thread` and sets up the
+//! thread-local state only for its own threads. Calling into Julia from any
+//! other thread, e.g. a rayon worker, crashes. Instead, send the work to the
+//! Julia thread with `JuliaExecutor::run`, which is Send and Sync, and convert
+//! Values into Rust types before returning them, since they can't leave the
+//! Julia thread.
+
+use std::marker::PhantomData;
+
+use sys::*;
+use super::Julia;
+
+/// RAII guard which keeps the current thread in the GC-safe state and
+/// restores the previous state on drop.
+///
+/// No Julia values may be accessed while the guard is alive.
+pub struct GcSafe {
+    ptls: jl_ptls_t,
+    state: i8,
+    // must be dropped on the thread it was created on.
+    _marker: PhantomData<*mut ()>,
+}
+
+impl GcSafe {
+    /// Transitions the current thread into the GC-safe state.
+    ///
+    /// This function is unsafe, because the current thread must be known to
+    /// Julia and must not access any Julia values until the guard is dropped.
+    pub unsafe fn enter() -> GcSafe {
+        let ptls = jl_get_ptls_states();
+        let state = jl_gc_safe_enter(ptls);
+        GcSafe {
+            ptls: ptls,
+            state: state,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl Drop for GcSafe {
+    fn drop(&mut self) {
+        unsafe {
+            jl_gc_safe_leave(self.ptls, self.state);
+        }
+    }
+}
+
+impl Julia {
+    /// Runs Rust code that doesn't touch any Julia values in the GC-safe
+    /// state, so that the garbage collector can run concurrently.
+    pub fn gc_safe<F, T>(&self, f: F) -> T
+    where
+        F: FnOnce() -> T,
+    {
+        let _guard = unsafe { GcSafe::enter() };
+        f()
+    }
+}
@@ -9,6 +9,7 @@ use sys::*;
 use error::{Result, Error};
 use string::{IntoCString, TryIntoString};
 use api::{Datatype, Function, IntoSymbol};
+use api::datatype::JlType;
 
 /// The trait implemented by every Julia type.
 pub trait JlValue<T>
@@ -52,6 +53,13 @@ where
         Ok(())
     }
 
+    /// Add a Rust closure as a finalizer, run with the object some time after
+    /// it's collected.
+    fn add_finalizer_fn<F: FnOnce(Value) + 'static>(&self, f: F) -> Result<()> {
+        let value = Value::new(self.lock()? as *mut jl_value_t)?;
+        ::api::gc::add_finalizer_fn(&value, Box::new(f))
+    }
+
     /// Consume and finalize self.
     fn finalize(self) -> Result<()> {
         unsafe {
@@ -131,6 +139,13 @@ where
         let raw = self.into_inner()? as *mut U;
         A::new(raw)
     }
+
+    /// Returns an object of another type with the same inner pointer, without
+    /// consuming self.
+    fn to_value<U, A: JlValue<U>>(&self) -> Result<A> {
+        let raw = self.lock()? as *mut U;
+        A::new(raw)
+    }
 }
 
 macro_rules! simple_jlvalue {
@@ -229,28 +244,6 @@ jlvalues! {
     pub struct Value(jl_value_t);
 }
 
-impl Expr {
-    /// Parse a string without evaluating it.
-    pub fn with_string(string: &str) -> Result<Expr> {
-        let len = string.len();
-        let string = string.into_cstring();
-        let string = string.as_ptr();
-
-        let raw = unsafe { jl_parse_string(string, len, 0, 0) };
-        jl_catch!();
-
-        Expr::new(raw as *mut _)
-    }
-
-    /// Evaluate expression.
-    pub fn expand(&self) -> Result<Value> {
-        let raw = self.lock()?;
-        let raw = unsafe { jl_expand(raw as *mut _) };
-        jl_catch!();
-        Value::new(raw)
-    }
-}
-
 impl Value {
     /// Nothing, Nil, Null, None.
     pub fn nothing() -> Value {
@@ -289,9 +282,19 @@ impl Value {
         !self._inner.is_poisoned()
     }
 
-    /// Checks if the Value is of a concrete Datatype.
-    pub fn isa(&self, other: &Datatype) -> Result<bool> {
-        let p = unsafe { jl_isa(self.lock()?, other.lock()? as *mut _) != 0 };
+    /// Checks if the Value is an instance of a type, e.g. a Datatype, a
+    /// Union or a UnionAll.
+    ///
+    /// ## Errors
+    ///
+    /// Returns Error::InvalidUnbox if `ty` isn't a type after all, e.g. a
+    /// `Type` made from some other value.
+    pub fn isa<T, U: JlType<T>>(&self, ty: &U) -> Result<bool> {
+        let ty = ty.lock()? as *mut jl_value_t;
+        if !unsafe { jl_is_type(ty) } {
+            return Err(Error::InvalidUnbox);
+        }
+        let p = unsafe { jl_isa(self.lock()?, ty) != 0 };
         Ok(p)
     }
 
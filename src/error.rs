@@ -12,6 +12,7 @@ use std::sync::PoisonError;
 use std::rc::Rc;
 
 use api::Exception;
+use api::parse::SyntaxError;
 
 /// Generic julia-rs Result type, used pretty much everywhere a failure might occur
 pub type Result<T> = result::Result<T, Error>;
@@ -57,6 +58,23 @@ pub enum Error {
     IntoStringError(IntoStringError),
     /// Wrapper for io::Error.
     IOError(io::Error),
+    /// An exception that occurred on another thread, detached from its Julia
+    /// value and carried as its string representation.
+    DetachedException(String),
+    /// The thread owning the Julia runtime has shut down.
+    ExecutorClosed,
+    /// A closure run on the thread owning the Julia runtime panicked,
+    /// carrying the panic message.
+    Panicked(String),
+    /// Evaluation was interrupted because it took too long.
+    Timeout,
+    /// A global variable is not defined, carrying its name.
+    UndefVar(String),
+    /// Code uses a construct which isn't allowed in a sandbox, carrying a
+    /// description of it.
+    Disallowed(String),
+    /// Code couldn't be parsed.
+    Syntax(SyntaxError),
 }
 
 impl fmt::Display for Error {
@@ -69,9 +87,16 @@ impl fmt::Display for Error {
             Error::FromUTF8Error(ref err) => write!(f, "FromUTF8Error({})", err),
             Error::IntoStringError(ref err) => write!(f, "IntoStringError({})", err),
             Error::IOError(ref err) => write!(f, "IOError({})", err),
+            Error::DetachedException(ref ex) => write!(f, "DetachedException({})", ex),
+            Error::Panicked(ref msg) => write!(f, "Panicked({})", msg),
+            Error::UndefVar(ref name) => write!(f, "UndefVar({})", name),
+            Error::Disallowed(ref what) => write!(f, "Disallowed({})", what),
+            Error::Syntax(ref err) => write!(f, "Syntax({})", err),
             Error::InvalidUnbox | Error::NotAFunction | Error::CallError | Error::EvalError |
             Error::NullPointer | Error::InvalidSymbol | Error::JuliaInitialized |
-            Error::PoisonError | Error::ResourceInUse => fmt::Debug::fmt(self, f),
+            Error::PoisonError | Error::ResourceInUse | Error::ExecutorClosed | Error::Timeout => {
+                fmt::Debug::fmt(self, f)
+            }
         }
     }
 }
@@ -95,6 +120,13 @@ impl error::Error for Error {
             Error::FromUTF8Error(ref err) => err.description(),
             Error::IntoStringError(ref err) => err.description(),
             Error::IOError(ref err) => err.description(),
+            Error::DetachedException(_) => "an exception occurred in the Julia thread",
+            Error::ExecutorClosed => "the Julia thread is no longer running",
+            Error::Panicked(_) => "a closure panicked in the Julia thread",
+            Error::Timeout => "evaluation was interrupted after timing out",
+            Error::UndefVar(_) => "the variable is not defined",
+            Error::Disallowed(_) => "the code uses a construct that isn't allowed in the sandbox",
+            Error::Syntax(_) => "the code has a syntax error",
         }
     }
 
@@ -107,9 +139,12 @@ impl error::Error for Error {
             Error::FromUTF8Error(ref err) => Some(err),
             Error::IntoStringError(ref err) => Some(err),
             Error::IOError(ref err) => Some(err),
+            Error::Syntax(ref err) => Some(err),
             Error::InvalidUnbox | Error::NotAFunction | Error::CallError | Error::EvalError |
             Error::NullPointer | Error::InvalidSymbol | Error::JuliaInitialized |
-            Error::PoisonError | Error::ResourceInUse => None,
+            Error::PoisonError | Error::ResourceInUse | Error::DetachedException(_) |
+            Error::ExecutorClosed | Error::Panicked(_) | Error::Timeout | Error::UndefVar(_) |
+            Error::Disallowed(_) => None,
         }
     }
 }
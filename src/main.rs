@@ -22,12 +22,13 @@
 //!   | | | | | | |/ _` |   | |/ // _)   |
 //!   | | |_| | | | (_| | _ |  ,/ \_ \   |  julia 0.6.0 (9036443)
 //!  _/ |\__'_|_|_|\__'_|(_)|_|   (__/   | julia-rs 0.1.2 (master-6a5b7d1)
-//! |__/                                 |
+//! |__/                                 |  x86_64-Linux, 8 CPU cores
 //!
 //! julia.rs> f(x) = 2x + pi
 //! ```
 
 
+#[macro_use]
 extern crate julia;
 extern crate colored;
 extern crate liner;
@@ -36,13 +37,16 @@ extern crate clap;
 use std::env;
 use std::fs::File;
 use std::io::ErrorKind;
+use std::mem;
 use std::path::Path;
+use std::process;
 
 use liner::{Context, History, KeyBindings, BasicCompleter};
 use colored::*;
-use clap::{Arg, App};
+use clap::{Arg, App, AppSettings, ArgMatches, SubCommand};
 
-use julia::api::{Julia, Value};
+use julia::api::{Julia, Value, Exception, ImageBuilder, Reloader, Reload, Parsed};
+use julia::api::parse;
 use julia::error::Error;
 use julia::version;
 
@@ -80,6 +84,8 @@ macro_rules! errprintln {
 }
 
 fn greet(jl: &Julia) {
+    let info = jl.system_info();
+    let system = format!("{}-{}, {} CPU cores", info.arch, info.os, info.cpu_cores);
     println!(
         r#"               {}
    {}       _ {}{}{}                   |  A fresh approach to technical computing
@@ -88,7 +94,7 @@ fn greet(jl: &Julia) {
   | | | | | | |/ _` |   | |/ // _)   |
   | | |_| | | | (_| | {} |  ,/ \_ \   |  {}
  _/ |\__'_|_|_|\__'_|{}|_|   (__/   |  {}
-|__/                                 |
+|__/                                 |  {}
 "#,
         "_".bright_green().bold(),
         "_".bright_blue().bold(),
@@ -101,7 +107,8 @@ fn greet(jl: &Julia) {
         "_".bright_yellow().bold(),
         jl.version(),
         "(_)".bright_yellow().bold(),
-        version::get()
+        version::get(),
+        system
     );
 }
 
@@ -162,15 +169,76 @@ fn eval_string(jl: &mut Julia, expr: &str) -> Option<Value> {
     if !ret.is_nothing() { Some(ret) } else { None }
 }
 
+fn is_interrupt(err: &Error) -> bool {
+    match *err {
+        Error::UnhandledException(ref ex) => {
+            match ex.root_cause() {
+                Ok(Exception::Interrupt(_)) => true,
+                _ => false,
+            }
+        }
+        _ => false,
+    }
+}
+
+/// Keeps reloading the watched files without a REPL until C-C.
+fn watch_files(jl: &mut Julia, reloader: &mut Reloader) {
+    if reloader.files().is_empty() {
+        return;
+    }
+
+    // C-C throws an InterruptException, which stops watching.
+    jl.set_exit_on_sigint(false);
+    println!("Watching for changes, press C-C to stop");
+
+    loop {
+        let reloads = reloader.poll(jl);
+        let interrupted = reloads.iter().any(|reload| match reload.result {
+            Err(ref err) => is_interrupt(err),
+            Ok(_) => false,
+        });
+        if interrupted {
+            break;
+        }
+
+        // sleeping in Julia lets C-C interrupt it.
+        match jl.eval_string("sleep(0.5)") {
+            Ok(_) => (),
+            Err(ref err) if is_interrupt(err) => break,
+            Err(err) => {
+                errprintln!("Error: {}", err);
+                break;
+            }
+        }
+    }
+}
+
+fn report_reload(reload: &Reload) {
+    if let Some(err) = reload.load_error() {
+        errprintln!("Exception: {}", err.error);
+        eprintln!(" > in {}:{}", err.file, err.line);
+        return;
+    }
+
+    match reload.result {
+        Ok(_) => println!("Reloaded {}", reload.path.display()),
+        Err(Error::UnhandledException(ref ex)) => errprintln!("Exception: {}", ex),
+        Err(ref err) => errprintln!("Error: {}", err),
+    }
+}
+
 fn is_whitespace(s: &String) -> bool {
     s.chars().all(|c| c.is_whitespace())
 }
 
-fn interactive(mut jl: Julia, quiet: bool) {
+fn interactive(mut jl: Julia, quiet: bool, mut reloader: Option<Reloader>) {
     if !quiet {
         greet(&jl);
     }
 
+    // C-C interrupts the evaluation instead of killing the REPL.
+    jl.set_exit_on_sigint(false);
+
     let home = env::var("HOME").unwrap();
     let history_path = format!("{}/.julia.rs_history", home);
     let history_path = Path::new(&history_path);
@@ -185,15 +253,21 @@ fn interactive(mut jl: Julia, quiet: bool) {
         key_bindings: KeyBindings::Emacs,
     };
     let ps1 = format!("{} ", "julia.rs>".bright_green().bold());
+    let ps2 = format!("{} ", "       ..".bright_green().bold());
+    let mut input = String::new();
 
     loop {
-        let line = con.read_line(&*ps1, None, &mut BasicCompleter::new::<String>(vec![]));
+        let prompt = if input.is_empty() { &*ps1 } else { &*ps2 };
+        let line = con.read_line(prompt, None, &mut BasicCompleter::new::<String>(vec![]));
         let line = match line {
-            Ok(ref line) if line.is_empty() || is_whitespace(line) => continue,
+            Ok(ref line) if input.is_empty() && (line.is_empty() || is_whitespace(line)) => continue,
             Ok(line) => line,
             Err(err) => {
                 match err.kind() {
-                    ErrorKind::Interrupted => continue,
+                    ErrorKind::Interrupted => {
+                        input.clear();
+                        continue;
+                    }
                     ErrorKind::UnexpectedEof => break,
                     err => {
                         eprintln!("Error: {:?}", err);
@@ -203,17 +277,34 @@ fn interactive(mut jl: Julia, quiet: bool) {
             }
         };
 
-        let ret = eval_string(&mut jl, &*line);
-        if let Some(ret) = ret {
-            print!("{}", ret);
+        if !input.is_empty() {
+            input.push('\n');
+        }
+        input.push_str(&line);
+
+        // keep reading lines until the input is a complete expression.
+        match parse::parse_all(&input) {
+            Ok(Parsed::Incomplete(_)) => continue,
+            Ok(Parsed::Error(err)) => errprintln!("Syntax error: {}", err),
+            _ => {
+                if let Some(ref mut reloader) = reloader {
+                    reloader.poll(&mut jl);
+                }
 
-            if let Err(i) = set_history(&mut jl, &ret) {
-                eprintln!("Warning: couldn't set answer history at {}", i);
+                let ret = eval_string(&mut jl, &*input);
+                if let Some(ret) = ret {
+                    print!("{}", ret);
+
+                    if let Err(i) = set_history(&mut jl, &ret) {
+                        eprintln!("Warning: couldn't set answer history at {}", i);
+                    }
+                }
+                println!();
             }
         }
-        println!();
 
-        if let Err(err) = con.history.push(line.into()) {
+        let input = mem::replace(&mut input, String::new());
+        if let Err(err) = con.history.push(input.into()) {
             eprintln!("Error: could not write line to history file\n > {}", err);
         }
     }
@@ -222,12 +313,65 @@ fn interactive(mut jl: Julia, quiet: bool) {
     history.commit_to_file();
 }
 
+/// Builds a system image, returning whether it succeeded.
+fn sysimg(matches: &ArgMatches) -> bool {
+    let mut builder = match ImageBuilder::new() {
+        Ok(builder) => builder,
+        Err(err) => {
+            errprintln!("An error occurred while initializing Julia:\n{}", err);
+            return false;
+        }
+    };
+
+    if let Some(modules) = matches.values_of("module") {
+        for module in modules {
+            if let Err(err) = builder.preload(module) {
+                eprintln!("Error: couldn't preload {}", module);
+                errprintln!("Error: {}", err);
+                return false;
+            }
+        }
+    }
+
+    if let Some(load) = matches.values_of("load") {
+        for filename in load {
+            let mut file = match File::open(filename) {
+                Ok(f) => f,
+                Err(e) => {
+                    eprintln!("Error: couldn't open file\n > {}", e);
+                    return false;
+                }
+            };
+
+            if let Err(err) = builder.julia_mut().load(&mut file, Some(filename)) {
+                errprintln!("Error: {}", err);
+                return false;
+            }
+        }
+    }
+
+    let output = matches.value_of("output").unwrap_or("sys.ji");
+    if let Err(err) = builder.save(output) {
+        eprintln!("Error: couldn't save the system image to {}", output);
+        errprintln!("Error: {}", err);
+        return false;
+    }
+    true
+}
+
 fn main() {
     let ver = version::get().to_string();
     let app = App::new("")
         .version(&*ver)
         .author("Szymon Walter <walter.szymon.98@gmail.com>")
         .about("Minimalistic interactive Julia REPL in Rust")
+        .setting(AppSettings::DisableVersion)
+        .arg(Arg::with_name("version").short("V").long("version").help(
+            "Prints version information",
+        ))
+        .arg(Arg::with_name("verbose").short("v").long("verbose").help(
+            "With --version, also prints Julia and system information",
+        ))
         .arg(
             Arg::with_name("eval")
                 .short("e")
@@ -276,12 +420,67 @@ fn main() {
         .arg(Arg::with_name("repl").short("i").long("interactive").help(
             "Interactive mode; REPL runs and isinteractive() is true",
         ))
+        .arg(Arg::with_name("watch").short("w").long("watch").help(
+            "Reload files given with --load when they change",
+        ))
         .arg(Arg::with_name("quiet").short("q").long("quiet").help(
             "Quiet startup (no banner)",
-        ));
+        ))
+        .arg(
+            Arg::with_name("sysimage")
+                .short("J")
+                .long("sysimage")
+                .value_name("FILE")
+                .help("Start up with the given system image file")
+                .takes_value(true),
+        )
+        .subcommand(
+            SubCommand::with_name("sysimg")
+                .about("Create a system image with preloaded modules")
+                .arg(
+                    Arg::with_name("output")
+                        .short("o")
+                        .long("output")
+                        .value_name("FILE")
+                        .help("Write the image to FILE (default: sys.ji)")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("module")
+                        .short("m")
+                        .long("module")
+                        .value_name("MODULE")
+                        .help("Preload MODULE with using")
+                        .multiple(true)
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("load")
+                        .short("L")
+                        .long("load")
+                        .value_name("FILE")
+                        .help("Load FILE into the image")
+                        .multiple(true)
+                        .takes_value(true),
+                ),
+        );
 
     let matches = app.get_matches();
 
+    if let Some(matches) = matches.subcommand_matches("sysimg") {
+        if !sysimg(matches) {
+            process::exit(1);
+        }
+        return;
+    }
+
+    let version = matches.is_present("version");
+    let verbose = matches.is_present("verbose");
+    if version && !verbose {
+        println!("{}", ver);
+        return;
+    }
+
     let eval = matches.values_of("eval");
     let print = matches.values_of("print");
     let load = matches.values_of("load");
@@ -289,8 +488,14 @@ fn main() {
     let dlopen = matches.values_of("dlopen");
     let repl = matches.is_present("repl");
     let quiet = matches.is_present("quiet");
+    let watch = matches.is_present("watch");
+    let sysimage = matches.value_of("sysimage");
 
-    let mut jl = match Julia::new() {
+    let jl = match sysimage {
+        Some(image) => Julia::with_image(image),
+        None => Julia::new(),
+    };
+    let mut jl = match jl {
         Ok(jl) => jl,
         Err(err) => {
             errprintln!("An error occurred while initializing Julia:\n{}", err);
@@ -298,30 +503,33 @@ fn main() {
         }
     };
 
+    if version {
+        println!("{}\n{}", ver, jl.system_info());
+        return;
+    }
+
     let mut repl_default = true;
 
-    let mut dlinclude = String::new();
     if let Some(paths) = dlpath {
         for path in paths {
-            dlinclude.push_str(&format!("push!(Libdl.DL_LOAD_PATH, \"{}\")\n", path));
+            if let Err(err) = julia!("push!(Libdl.DL_LOAD_PATH, $path)", path) {
+                eprintln!("Error: couldn't add {} to the library path", path);
+                errprintln!("Error: {}", err);
+            }
         }
     }
 
     if let Some(paths) = dlopen {
         for path in paths {
-            dlinclude.push_str(&format!(
-                "{} = Libdl.dlopen(\"{}\")",
-                path.replace(".so", ""),
-                path
-            ));
+            let name = path.replace(".so", "");
+            let lib = julia!("Libdl.dlopen($path)", path).and_then(|lib| jl.main().set(&*name, &lib));
+            if let Err(err) = lib {
+                eprintln!("Error: couldn't open {}", path);
+                errprintln!("Error: {}", err);
+            }
         }
     }
 
-    if !dlinclude.is_empty() {
-        jl.load(&mut dlinclude.as_bytes(), Some("dlinclude.jl"))
-            .expect("Could not load dlinclude.jl");
-    }
-
     if let Some(eval) = eval {
         for expr in eval {
             eval_string(&mut jl, expr);
@@ -338,20 +546,35 @@ fn main() {
         repl_default = false;
     }
 
+    let mut reloader = None;
     if let Some(load) = load {
-        for filename in load {
-            let mut file = match File::open(filename) {
-                Ok(f) => f,
-                Err(e) => {
-                    eprintln!("Error: couldn't open file\n > {}", e);
-                    continue;
+        if watch {
+            let mut watcher = Reloader::new();
+            watcher.on_reload(report_reload);
+            for filename in load {
+                let main = jl.main().clone();
+                match watcher.watch(&mut jl, filename, &main) {
+                    Err(Error::UnhandledException(ex)) => errprintln!("Exception: {}", ex),
+                    Err(err) => errprintln!("Error: {}", err),
+                    _ => (),
                 }
-            };
+            }
+            reloader = Some(watcher);
+        } else {
+            for filename in load {
+                let mut file = match File::open(filename) {
+                    Ok(f) => f,
+                    Err(e) => {
+                        eprintln!("Error: couldn't open file\n > {}", e);
+                        continue;
+                    }
+                };
 
-            match jl.load(&mut file, Some(filename)) {
-                Err(Error::UnhandledException(ex)) => errprintln!("Exception: {}", ex),
-                Err(err) => errprintln!("Error: {}", err),
-                _ => (),
+                match jl.load(&mut file, Some(filename)) {
+                    Err(Error::UnhandledException(ex)) => errprintln!("Exception: {}", ex),
+                    Err(err) => errprintln!("Error: {}", err),
+                    _ => (),
+                }
             }
         }
         repl_default = false;
@@ -362,6 +585,8 @@ fn main() {
     if repl {
         jl.load(&mut INITREPL_JL.as_bytes(), Some("initrepl.jl"))
             .expect("Could not load initrepl.jl");
-        interactive(jl, quiet);
+        interactive(jl, quiet, reloader);
+    } else if let Some(mut reloader) = reloader {
+        watch_files(&mut jl, &mut reloader);
     }
 }
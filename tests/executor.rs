@@ -0,0 +1,116 @@
+extern crate julia;
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake};
+use std::thread::{self, Thread};
+
+use julia::api::{JuliaExecutor, JlValue, JlChannel, Task};
+use julia::error::Error;
+
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+fn block_on<F: Future>(mut future: F) -> F::Output {
+    let waker = Arc::new(ThreadWaker(thread::current())).into();
+    let mut cx = Context::from_waker(&waker);
+    let mut future = unsafe { Pin::new_unchecked(&mut future) };
+
+    loop {
+        match future.as_mut().poll(&mut cx) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => thread::park(),
+        }
+    }
+}
+
+// Julia can only be initialized once per process, so everything runs on a
+// single executor.
+#[test]
+fn executor() {
+    let jl = JuliaExecutor::new().unwrap();
+
+    futures(&jl);
+    streams(&jl);
+    panics(&jl);
+
+    jl.shutdown().unwrap();
+    assert!(!jl.is_running());
+    match block_on(jl.eval_async::<_, f64>("1.0")) {
+        Err(Error::ExecutorClosed) => (),
+        other => panic!("expected ExecutorClosed, got {:?}", other),
+    }
+}
+
+fn futures(jl: &JuliaExecutor) {
+    let x: f64 = jl.eval("sqrt(4.0)").unwrap();
+    assert_eq!(x, 2.0);
+
+    let slow = jl.task_async::<_, f64>(|jl| {
+        let task = jl.eval_string("@schedule (sleep(0.2); 42.0)")?;
+        Task::from_value(task)
+    });
+    let fast = jl.eval_async::<_, f64>("sqrt(9.0)");
+    let call = jl.call_async::<_, _, f64>("Base.max", vec![1.0, 3.0, 2.0]);
+
+    assert_eq!(block_on(fast).unwrap(), 3.0);
+    assert_eq!(block_on(call).unwrap(), 3.0);
+    assert_eq!(block_on(slow).unwrap(), 42.0);
+
+    match block_on(jl.eval_async::<_, f64>("error(\"boom\")")) {
+        Err(Error::DetachedException(_)) => (),
+        other => panic!("expected DetachedException, got {:?}", other),
+    }
+    match block_on(jl.task_async::<_, f64>(|jl| {
+        let task = jl.eval_string("@schedule error(\"boom\")")?;
+        Task::from_value(task)
+    })) {
+        Err(_) => (),
+        Ok(x) => panic!("expected the failed task to error, got {}", x),
+    }
+}
+
+fn streams(jl: &JuliaExecutor) {
+    let mut squares = jl.stream::<_, f64>(|jl| {
+        let channel = jl.eval_string("Channel(c -> for i in 1:4; put!(c, i^2 / 1.0); sleep(0.05); end)")?;
+        JlChannel::with_value(channel)
+    });
+
+    let mut taken = vec![];
+    while let Some(x) = block_on(squares.next()) {
+        taken.push(x.unwrap());
+    }
+    assert_eq!(taken, vec![1.0, 4.0, 9.0, 16.0]);
+    // the stream stays finished once the channel is closed.
+    assert!(block_on(squares.next()).is_none());
+
+    let mut failed = jl.stream::<_, f64>(|jl| {
+        let channel = jl.eval_string("error(\"no channel\")")?;
+        JlChannel::with_value(channel)
+    });
+    assert!(block_on(failed.next()).unwrap().is_err());
+    assert!(block_on(failed.next()).is_none());
+}
+
+fn panics(jl: &JuliaExecutor) {
+    match jl.run::<_, ()>(|_| panic!("boom")) {
+        Err(Error::Panicked(msg)) => assert_eq!(msg, "boom"),
+        other => panic!("expected Panicked, got {:?}", other),
+    }
+    match block_on(jl.spawn::<_, ()>(|_| panic!("async boom"))) {
+        Err(Error::Panicked(msg)) => assert_eq!(msg, "async boom"),
+        other => panic!("expected Panicked, got {:?}", other),
+    }
+
+    // the Julia thread survives for every clone.
+    let clone = jl.clone();
+    assert!(clone.is_running());
+    let x: f64 = clone.eval("1.0 + 1.0").unwrap();
+    assert_eq!(x, 2.0);
+}
@@ -0,0 +1,44 @@
+extern crate julia;
+
+use std::convert::TryFrom;
+use std::io::Cursor;
+
+use julia::api::{Julia, Value};
+use julia::error::{Result, Error};
+
+fn load_script(jl: &mut Julia, script: &str) -> Result<Value> {
+    jl.load(&mut Cursor::new(script.to_owned()), Some("script.jl"))
+}
+
+fn error_line(jl: &mut Julia, script: &str) -> usize {
+    match load_script(jl, script) {
+        Err(Error::UnhandledException(ex)) => {
+            let err = ex.load_error().unwrap().expect("not a LoadError");
+            assert_eq!(err.file, "script.jl");
+            err.line
+        }
+        Err(err) => panic!("expected a LoadError, got {:?}", err),
+        Ok(value) => panic!("expected a LoadError, got {}", value),
+    }
+}
+
+#[test]
+fn load() {
+    let mut jl = Julia::new().unwrap();
+
+    let ret = load_script(&mut jl, "x = 2\nf(y) =\n    x * y\nf(21)\n").unwrap();
+    assert_eq!(i64::try_from(&ret).unwrap(), 42);
+
+    assert_eq!(error_line(&mut jl, "a = 1\n\nb = 2\nerror(\"boom\")\n"), 4);
+    assert_eq!(error_line(&mut jl, "g() =\n    1\n# comment\n\n  undefined_name\n"), 5);
+    assert_eq!(error_line(&mut jl, "a = 1\nb = (1,\n"), 2);
+    assert_eq!(error_line(&mut jl, "a = 1\nb = 1 +* 2\n"), 2);
+
+    // the script is read in several chunks.
+    let mut long = String::new();
+    for i in 0..2000 {
+        long.push_str(&format!("x{} = {}\n", i % 10, i));
+    }
+    long.push_str("function h()\n    error(\"boom\")\nend\nh()\n");
+    assert_eq!(error_line(&mut jl, &long), 2004);
+}
@@ -0,0 +1,59 @@
+extern crate julia;
+
+use std::collections::HashMap;
+use std::convert::TryFrom;
+
+use julia::api::{Julia, Module, Value, Symbol};
+use julia::error::Error;
+
+fn strings(names: Vec<Symbol>) -> Vec<String> {
+    names.iter().map(|name| String::try_from(name).unwrap()).collect()
+}
+
+#[test]
+fn module() {
+    let jl = Julia::new().unwrap();
+
+    let module = Module::new("Introspected", Some(jl.main())).unwrap();
+    module.add_standard_imports().unwrap();
+    module.set_const("a", &Value::from(1i64)).unwrap();
+    module.set("b", &Value::from(2i64)).unwrap();
+    module.export("a").unwrap();
+
+    assert_eq!(String::try_from(&module.name().unwrap()).unwrap(), "Introspected");
+    assert!(module.is_submodule_of(jl.main()).unwrap());
+
+    // names
+    let exported = strings(module.names(false, false).unwrap());
+    assert!(exported.contains(&"a".to_owned()));
+    assert!(!exported.contains(&"b".to_owned()));
+    let all = strings(module.names(true, false).unwrap());
+    assert!(all.contains(&"a".to_owned()));
+    assert!(all.contains(&"b".to_owned()));
+    assert!(!all.contains(&"println".to_owned()));
+
+    // bindings
+    let bindings: HashMap<String, Value> = module
+        .bindings()
+        .unwrap()
+        .map(|(name, value)| (String::try_from(&name).unwrap(), value))
+        .collect();
+    assert_eq!(i64::try_from(&bindings["a"]).unwrap(), 1);
+    assert_eq!(i64::try_from(&bindings["b"]).unwrap(), 2);
+
+    // is_const and is_defined
+    assert!(module.is_const("a").unwrap());
+    assert!(!module.is_const("b").unwrap());
+    assert!(module.is_defined("b").unwrap());
+    assert!(!module.is_defined("c").unwrap());
+
+    match module.global("c") {
+        Err(Error::UndefVar(name)) => assert_eq!(name, "c"),
+        other => panic!("expected UndefVar, got {:?}", other),
+    }
+
+    // eval_string parses exactly the given code, up to its last byte.
+    let s = module.eval_string("s = \"abc\"").unwrap();
+    assert_eq!(String::try_from(&s).unwrap(), "abc");
+    assert_eq!(i64::try_from(&module.eval_string("b + 1 # comment").unwrap()).unwrap(), 3);
+}
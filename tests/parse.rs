@@ -0,0 +1,52 @@
+extern crate julia;
+
+use julia::api::{Julia, Parsed, SyntaxError};
+use julia::api::parse::{parse, parse_all};
+
+fn error<T>(parsed: Parsed<T>) -> SyntaxError {
+    match parsed {
+        Parsed::Error(err) => err,
+        Parsed::Incomplete(err) => panic!("expected a syntax error, got incomplete input: {}", err),
+        Parsed::Complete(_) => panic!("expected a syntax error, got complete input"),
+    }
+}
+
+#[test]
+fn parse_diagnostics() {
+    let _jl = Julia::new().unwrap();
+
+    // complete code
+    let parsed = parse("1 + 2").unwrap();
+    assert!(parsed.is_complete());
+    assert!(parsed.ok().unwrap().is_expr());
+    assert!(parse("  x  # a comment\n").unwrap().is_complete());
+
+    // incomplete code, which a REPL should read more lines for
+    for code in &["begin\n    x", "function f()", "(1, 2", "\"abc", "a = [1,\n"] {
+        assert!(parse(code).unwrap().is_incomplete(), "{:?} isn't incomplete", code);
+    }
+
+    // syntax errors
+    let err = error(parse("").unwrap());
+    assert_eq!(err.start.offset, 0);
+    let err = error(parse("1\n2").unwrap());
+    assert_eq!(err.start.line, 2);
+    assert_eq!(err.start.column, 1);
+    assert!(!parse("x = )").unwrap().is_incomplete());
+    assert!(!parse("x = )").unwrap().is_complete());
+
+    // any number of expressions
+    match parse_all("a = 1\nb = 2\n# c\n").unwrap() {
+        Parsed::Complete(exprs) => assert_eq!(exprs.len(), 2),
+        _ => panic!("expected complete input"),
+    }
+    match parse_all("").unwrap() {
+        Parsed::Complete(exprs) => assert!(exprs.is_empty()),
+        _ => panic!("expected complete input"),
+    }
+    assert!(parse_all("a = 1\nfor i in 1:3\n").unwrap().is_incomplete());
+    let err = error(parse_all("a = 1\n  b = )\nc = 3\n").unwrap());
+    assert_eq!(err.start.line, 2);
+    assert_eq!(err.start.column, 3);
+    assert!(parse_all("a = 1\n  b = )\n").unwrap().into_result().is_err());
+}
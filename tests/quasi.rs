@@ -0,0 +1,65 @@
+#[macro_use]
+extern crate julia;
+
+use std::convert::TryFrom;
+
+use julia::api::{Julia, Value};
+
+fn int(value: Value) -> i64 {
+    i64::try_from(&value).unwrap()
+}
+
+#[test]
+fn quasi() {
+    let mut jl = Julia::new().unwrap();
+
+    let x = 3i64;
+    let (a, b) = (1i64, 2i64);
+    let xs = jl.eval_string("[1, 2, 3]").unwrap();
+
+    // spacing and operators are kept as written.
+    let ret = julia!("sum(2 .* $xs)", xs).unwrap();
+    assert_eq!(int(ret), 12);
+    let ret = julia!("$a .== $b", a, b).unwrap();
+    assert!(bool::try_from(&ret).unwrap() == false);
+    let ret = julia!("length(@show ($a, $b))", a, b).unwrap();
+    assert_eq!(int(ret), 2);
+    let ret = julia!("size([$a ($b)])", a, b).unwrap();
+    assert_eq!(ret.to_string(), "(1, 2)");
+    let ret = julia!("$xs'", xs).unwrap();
+    assert_eq!(ret.to_string(), julia!("[1 2 3]").unwrap().to_string());
+    let ret = julia!(r#"
+        # a comment
+        if $x > 0
+            (1)
+        else
+            2
+        end
+    "#, x).unwrap();
+    assert_eq!(int(ret), 1);
+
+    // values of arbitrary expressions, with the code compiled only once.
+    for i in 0..3i64 {
+        let ret = julia!("$y + length($xs)", y = i * 10, xs).unwrap();
+        assert_eq!(int(ret), i * 10 + 3);
+    }
+
+    // strings are values, not code.
+    let name = "\"); error(\"";
+    let ret = julia!("length($name)", name).unwrap();
+    assert_eq!(int(ret) as usize, name.len());
+
+    // long code doesn't run into the recursion limit.
+    let ret = julia!("
+        $a + $a + $a + $a + $a + $a + $a + $a + $a + $a + $a + $a + $a + $a + $a + $a +
+        $a + $a + $a + $a + $a + $a + $a + $a + $a + $a + $a + $a + $a + $a + $a + $a +
+        $a + $a + $a + $a + $a + $a + $a + $a + $a + $a + $a + $a + $a + $a + $a + $a +
+        $a + $a + $a + $a + $a + $a + $a + $a + $a + $a + $a + $a + $a + $a + $a + $a
+    ", a).unwrap();
+    assert_eq!(int(ret), 64);
+
+    // only names of arguments can be interpolated.
+    assert!(julia!("$z + 1", a).is_err());
+    assert!(julia!("$(a + 1)", a).is_err());
+    assert!(julia!("(1 + ", a).is_err());
+}
@@ -0,0 +1,66 @@
+extern crate julia;
+
+use std::convert::TryFrom;
+use std::time::{Duration, Instant};
+
+use julia::api::{Julia, Sandbox};
+use julia::error::Error;
+
+fn assert_disallowed(sandbox: &Sandbox, code: &str) {
+    match sandbox.eval(code) {
+        Err(Error::Disallowed(_)) => (),
+        Err(err) => panic!("{:?} wasn't rejected, but failed with {:?}", code, err),
+        Ok(value) => panic!("{:?} wasn't rejected, but returned {}", code, value),
+    }
+}
+
+#[test]
+fn sandbox() {
+    let mut jl = Julia::new().unwrap();
+
+    let mut sandbox = Sandbox::new(&jl).unwrap();
+    for name in &["+", "*", "==", "Int"] {
+        sandbox.allow_from(jl.base(), name).unwrap();
+    }
+
+    // names and constructs reaching outside of the sandbox.
+    assert_disallowed(&sandbox, "ccall(:getpid, Cint, ())");
+    assert_disallowed(&sandbox, "Base.Filesystem.rm(\"/tmp/x\")");
+    assert_disallowed(&sandbox, "eval(:(1 + 1))");
+    assert_disallowed(&sandbox, "import Base.run");
+
+    // methods added to functions and types from other modules.
+    assert_disallowed(&sandbox, "+(a::Int,b::Int)=0");
+    assert_disallowed(&sandbox, "function +(a::Int, b::Int) 0 end");
+    assert_disallowed(&sandbox, "a::Int * b::Int = 0");
+    assert_disallowed(&sandbox, "==(a::T, b::T) where T = false");
+    assert_disallowed(&sandbox, "(::Type{Int64})(x::Int32) = 1");
+    assert_disallowed(&sandbox, "(f::typeof(+))(x) = 1");
+    assert_disallowed(&sandbox, "Int64(x::Int32) = 1");
+    assert_disallowed(&sandbox, "Base.:+(a::Int, b::Int) = 0");
+    assert_disallowed(&sandbox, "function + end");
+    assert_disallowed(&sandbox, "const plus = +; plus(a::Int, b::Int) = 0");
+    assert_eq!(i64::try_from(&jl.eval_string("1 + 2").unwrap()).unwrap(), 3);
+
+    // functions of the sandbox's own are fine, including new methods.
+    let ret = sandbox.eval("f(x) = x * x; f(x, y) = f(x) + y; f(3, 1)").unwrap();
+    assert_eq!(i64::try_from(&ret).unwrap(), 10);
+    let ret = sandbox.eval("f(x::Int) = 0; f(2)").unwrap();
+    assert_eq!(i64::try_from(&ret).unwrap(), 0);
+    let ret = sandbox.eval("g = x -> x + 1; g(1)").unwrap();
+    assert_eq!(i64::try_from(&ret).unwrap(), 2);
+
+    // timeouts, also in loops without safepoints.
+    sandbox.set_timeout(Some(Duration::from_millis(200)));
+    for code in &["while true; end", "x = 0; while true; x += 1; end"] {
+        let start = Instant::now();
+        match sandbox.eval(code) {
+            Err(Error::Timeout) => (),
+            other => panic!("{:?} didn't time out: {:?}", code, other.map(|v| v.to_string())),
+        }
+        assert!(start.elapsed() < Duration::from_secs(10));
+    }
+
+    let ret = sandbox.eval("1 + 1").unwrap();
+    assert_eq!(i64::try_from(&ret).unwrap(), 2);
+}